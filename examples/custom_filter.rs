@@ -1,4 +1,9 @@
-use color_eyre::{eyre::Report, eyre::WrapErr, Section};
+use color_eyre::{
+    config::{FrameFilter, FrameMatcher},
+    eyre::Report,
+    eyre::WrapErr,
+    Section,
+};
 use tracing::{info, instrument};
 
 #[instrument]
@@ -8,21 +13,11 @@ fn main() -> Result<(), Report> {
     install_tracing();
 
     color_eyre::config::HookBuilder::default()
-        .add_frame_filter(Box::new(|frames| {
-            let filters = &["custom_filter::main"];
-
-            frames.retain(|frame| {
-                !filters.iter().any(|f| {
-                    let name = if let Some(name) = frame.name.as_ref() {
-                        name.as_str()
-                    } else {
-                        return true;
-                    };
-
-                    name.starts_with(f)
-                })
-            });
-        }))
+        .add_frame_filter(
+            FrameFilter::new()
+                .hide(FrameMatcher::prefix("custom_filter::main"))
+                .into(),
+        )
         .install()
         .unwrap();
 
@@ -1,48 +1,32 @@
 //! Configuration options for customizing the behavior of the provided panic
 //! and error reporting hooks
+pub use crate::filter::{FrameFilter, FrameMatcher};
 use crate::{
+    crash_report::CrashReportDir,
+    filter::EnvRules,
+    paths::PathRemapper,
     section::PanicMessage,
     writers::{EnvSection, WriterExt},
 };
 use fmt::Display;
 use indenter::{indented, Format};
+use once_cell::sync::OnceCell;
 use owo_colors::{style, OwoColorize, Style};
 use std::env;
 use std::fmt::Write as _;
-use std::{fmt, path::PathBuf, sync::Arc};
-
-#[derive(Debug)]
-struct InstallError;
-
-impl fmt::Display for InstallError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("could not install the BacktracePrinter as another was already installed")
-    }
-}
-
-impl std::error::Error for InstallError {}
-
-#[derive(Debug)]
-struct InstallThemeError;
-
-impl fmt::Display for InstallThemeError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("could not set the provided `Theme` globally as another was already set")
-    }
-}
-
-impl std::error::Error for InstallThemeError {}
-
-#[derive(Debug)]
-struct InstallColorSpantraceThemeError;
-
-impl fmt::Display for InstallColorSpantraceThemeError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("could not set the provided `Theme` via `color_spantrace::set_theme` globally as another was already set")
-    }
-}
-
-impl std::error::Error for InstallColorSpantraceThemeError {}
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
 
 /// A struct that represents a theme that is used by `color_eyre`
 #[derive(Debug, Copy, Clone, Default)]
@@ -65,6 +49,10 @@ pub struct Theme {
     pub(crate) panic_file: Style,
     pub(crate) panic_line_number: Style,
     pub(crate) hidden_frames: Style,
+    pub(crate) syntax_keyword: Style,
+    pub(crate) syntax_string: Style,
+    pub(crate) syntax_comment: Style,
+    pub(crate) syntax_type: Style,
 }
 
 macro_rules! theme_setters {
@@ -106,6 +94,10 @@ impl Theme {
             hidden_frames: style().bright_cyan(),
             spantrace_target: style().bright_red(),
             spantrace_fields: style().bright_cyan(),
+            syntax_keyword: style().bright_blue(),
+            syntax_string: style().green(),
+            syntax_comment: style().bright_black(),
+            syntax_type: style().bright_yellow(),
         }
     }
 
@@ -132,6 +124,10 @@ impl Theme {
             panic_file: style().purple(),
             panic_line_number: style().purple(),
             hidden_frames: style().blue(),
+            syntax_keyword: style().blue(),
+            syntax_string: style().green(),
+            syntax_comment: style().bright_black(),
+            syntax_type: style().purple(),
         }
     }
 
@@ -173,6 +169,14 @@ impl Theme {
         panic_line_number,
         /// Styles the "N frames hidden" message
         hidden_frames,
+        /// Styles keywords and lifetimes in source snippets, if the `syntax-highlighting` feature is enabled
+        syntax_keyword,
+        /// Styles string and character literals in source snippets, if the `syntax-highlighting` feature is enabled
+        syntax_string,
+        /// Styles comments in source snippets, if the `syntax-highlighting` feature is enabled
+        syntax_comment,
+        /// Styles types in source snippets, if the `syntax-highlighting` feature is enabled
+        syntax_type,
     }
 }
 
@@ -188,54 +192,109 @@ pub struct Frame {
     pub lineno: Option<u32>,
     /// source file path
     pub filename: Option<PathBuf>,
+    /// source column number
+    pub colno: Option<u32>,
+    /// instruction pointer of the frame
+    pub ip: usize,
+    /// base address of the module (executable or shared library) containing
+    /// the frame, if known
+    pub module_base: Option<usize>,
+    own_code: bool,
+    dependency_code: bool,
+    /// name displayed instead of `name`, e.g. the async fn of a relabeled
+    /// `{{closure}}` frame
+    label: Option<String>,
 }
 
-#[derive(Debug)]
-struct StyledFrame<'a>(&'a Frame, Theme);
+/// Remove the crate disambiguators of v0 mangled symbols, e.g. the
+/// `[c1f1a4ba060b9bfa]` in `core[c1f1a4ba060b9bfa]::pin::Pin`
+pub(crate) fn strip_crate_disambiguators(name: &str) -> Cow<'_, str> {
+    if !name.contains('[') {
+        return Cow::Borrowed(name);
+    }
+
+    let mut stripped = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(start) = rest.find('[') {
+        let end = rest[start + 1..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .map(|end| start + 1 + end);
+
+        match end.filter(|&end| end > start + 1 && rest[end..].starts_with(']')) {
+            Some(end) => {
+                stripped.push_str(&rest[..start]);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                stripped.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    stripped.push_str(rest);
+
+    Cow::Owned(stripped)
+}
+
+/// Split a symbol name into the path and its hash suffix, e.g. `::h1a2b...`
+fn split_hash_suffix(name: &str) -> (&str, Option<&str>) {
+    // (dodging a dep on the regex crate here)
+    let has_hash_suffix = name.len() > 19
+        && &name[name.len() - 19..name.len() - 16] == "::h"
+        && name[name.len() - 16..]
+            .chars()
+            .all(|x| x.is_ascii_hexdigit());
+
+    if has_hash_suffix {
+        let (name, hash) = name.split_at(name.len() - 19);
+        (name, Some(hash))
+    } else {
+        (name, None)
+    }
+}
+
+struct StyledFrame<'a>(&'a Frame, Theme, &'a FrameSettings, Verbosity);
 
 impl<'a> fmt::Display for StyledFrame<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self(frame, theme) = self;
+        let Self(frame, theme, settings, v) = self;
+        let v = *v;
 
         let is_dependency_code = frame.is_dependency_code();
 
         // Print frame index.
         write!(f, "{:>2}: ", frame.n)?;
 
-        // Does the function have a hash suffix?
-        // (dodging a dep on the regex crate here)
-        let name = frame.name.as_deref().unwrap_or("<unknown>");
-        let has_hash_suffix = name.len() > 19
-            && &name[name.len() - 19..name.len() - 16] == "::h"
-            && name[name.len() - 16..]
-                .chars()
-                .all(|x| x.is_ascii_hexdigit());
-
-        let hash_suffix = if has_hash_suffix {
-            &name[name.len() - 19..]
-        } else {
-            "<unknown>"
-        };
+        let name = frame.label.as_deref().or(frame.name.as_deref());
+        let (name, hash_suffix) = split_hash_suffix(name.unwrap_or("<unknown>"));
+        let hash_suffix = hash_suffix.unwrap_or("<unknown>");
 
-        // Print function name.
-        let name = if has_hash_suffix {
-            &name[..name.len() - 19]
+        // Full names are displayed along with all frames.
+        let verbose = show_hidden();
+        let name = if settings.simplify_symbol_names && !verbose {
+            Cow::Owned(crate::symbols::simplify(name))
         } else {
-            name
+            Cow::Borrowed(name)
         };
 
+        // Print function name.
         if is_dependency_code {
             write!(f, "{}", (name).style(theme.dependency_code))?;
         } else {
             write!(f, "{}", (name).style(theme.crate_code))?;
         }
 
-        write!(f, "{}", (hash_suffix).style(theme.code_hash))?;
+        if settings.display_symbol_hashes || verbose {
+            write!(f, "{}", (hash_suffix).style(theme.code_hash))?;
+        }
 
         let mut separated = f.header("\n");
 
         // Print source location, if known.
-        let file = frame.filename.as_ref().map(|path| path.display());
+        let file = frame
+            .filename
+            .as_ref()
+            .map(|path| settings.path_remapper.shorten(path));
         let file: &dyn fmt::Display = if let Some(ref filename) = file {
             filename
         } else {
@@ -244,33 +303,59 @@ impl<'a> fmt::Display for StyledFrame<'a> {
         let lineno = frame
             .lineno
             .map_or("<unknown line>".to_owned(), |x| x.to_string());
+        let colno = frame.colno.map_or(String::new(), |x| {
+            format!(":{}", x.style(theme.line_number))
+        });
+        let url = frame
+            .filename
+            .as_ref()
+            .and_then(|path| settings.hyperlinks.url(path, frame.lineno, frame.colno));
         write!(
             &mut separated.ready(),
-            "    at {}:{}",
-            file.style(theme.file),
-            lineno.style(theme.line_number),
+            "    at {}",
+            crate::fmt::Hyperlink(
+                url.as_deref(),
+                format_args!(
+                    "{}:{}{}",
+                    file.style(theme.file),
+                    lineno.style(theme.line_number),
+                    colno
+                )
+            ),
         )?;
 
-        let v = if std::thread::panicking() {
-            panic_verbosity()
-        } else {
-            lib_verbosity()
-        };
+        // Print the address, for symbolicating backtraces of stripped binaries.
+        if v >= Verbosity::Full {
+            let mut address = format!("    at address {:#x}", frame.ip);
+            if let Some(base) = frame.module_base {
+                write!(
+                    &mut address,
+                    " (module base {:#x}, offset {:#x})",
+                    base,
+                    frame.ip.wrapping_sub(base)
+                )?;
+            }
+            write!(&mut separated.ready(), "{}", address.style(theme.code_hash))?;
+        }
 
         // Maybe print source.
-        if v >= Verbosity::Full {
-            write!(&mut separated.ready(), "{}", SourceSection(frame, *theme))?;
+        if v >= Verbosity::Full && (settings.dependency_source_snippets || !is_dependency_code) {
+            write!(
+                &mut separated.ready(),
+                "{}",
+                SourceSection(frame, *theme, settings)
+            )?;
         }
 
         Ok(())
     }
 }
 
-struct SourceSection<'a>(&'a Frame, Theme);
+struct SourceSection<'a>(&'a Frame, Theme, &'a FrameSettings);
 
 impl fmt::Display for SourceSection<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self(frame, theme) = self;
+        let Self(frame, theme, settings) = self;
 
         let (lineno, filename) = match (frame.lineno, frame.filename.as_ref()) {
             (Some(a), Some(b)) => (a, b),
@@ -278,23 +363,23 @@ impl fmt::Display for SourceSection<'_> {
             _ => return Ok(()),
         };
 
-        let file = match std::fs::File::open(filename) {
-            Ok(file) => file,
-            Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
-            e @ Err(_) => e.unwrap(),
-        };
-
         use std::fmt::Write;
-        use std::io::BufRead;
 
         // Extract relevant lines.
-        let reader = std::io::BufReader::new(file);
-        let start_line = lineno - 2.min(lineno - 1);
-        let surrounding_src = reader.lines().skip(start_line as usize - 1).take(5);
+        let start_line = lineno.saturating_sub(settings.source_lines_before).max(1);
+        let end_line = lineno.saturating_add(settings.source_lines_after);
+        let surrounding_src = match settings
+            .source_provider
+            .source_lines(filename, start_line..=end_line)
+        {
+            Ok(Some(lines)) => lines,
+            // A missing or unreadable source file shouldn't break the report.
+            Ok(None) | Err(_) => return Ok(()),
+        };
+
         let mut separated = f.header("\n");
         let mut f = separated.in_progress();
-        for (line, cur_line_no) in surrounding_src.zip(start_line..) {
-            let line = line.unwrap();
+        for (line, cur_line_no) in surrounding_src.iter().zip(start_line..) {
             if cur_line_no == lineno {
                 write!(
                     &mut f,
@@ -304,6 +389,8 @@ impl fmt::Display for SourceSection<'_> {
                     line.style(theme.active_line),
                 )?;
             } else {
+                #[cfg(feature = "syntax-highlighting")]
+                let line = crate::highlight::Highlighted(line, *theme);
                 write!(&mut f, "{:>8} │ {}", cur_line_no, line)?;
             }
             f = separated.ready();
@@ -313,8 +400,116 @@ impl fmt::Display for SourceSection<'_> {
     }
 }
 
+/// A source of the code snippets displayed for backtrace frames
+///
+/// # Details
+///
+/// Snippets are displayed for each frame when `RUST_BACKTRACE=full` is set. By
+/// default they are read from the local filesystem via `FileSystemSource`,
+/// which only works if the paths recorded in the binary's debug info exist on
+/// the machine displaying the report. A custom provider can serve sources
+/// that were embedded at build time, extracted from an archive or checked out
+/// elsewhere.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::config::{HookBuilder, SourceProvider};
+/// use std::{io, ops::RangeInclusive, path::Path};
+///
+/// struct EmbeddedSource;
+///
+/// impl SourceProvider for EmbeddedSource {
+///     fn source_lines(
+///         &self,
+///         path: &Path,
+///         lines: RangeInclusive<u32>,
+///     ) -> io::Result<Option<Vec<String>>> {
+///         let source = match path.to_str() {
+///             Some(p) if p.ends_with("src/main.rs") => "fn main() {\n    run();\n}",
+///             _ => return Ok(None),
+///         };
+///
+///         let start = *lines.start() as usize - 1;
+///         let count = *lines.end() as usize - start;
+///         Ok(Some(
+///             source
+///                 .lines()
+///                 .skip(start)
+///                 .take(count)
+///                 .map(String::from)
+///                 .collect(),
+///         ))
+///     }
+/// }
+///
+/// HookBuilder::default()
+///     .source_provider(EmbeddedSource)
+///     .install()
+///     .unwrap();
+/// ```
+pub trait SourceProvider: Send + Sync + 'static {
+    /// Return the given 1-based, inclusive range of `lines` of the file at
+    /// `path`.
+    ///
+    /// Returns `Ok(None)` if the file is unknown to this provider. The returned
+    /// lines start at `lines.start()`, and may be fewer than requested if the
+    /// file ends earlier. Errors are not displayed, the snippet is omitted
+    /// instead.
+    fn source_lines(
+        &self,
+        path: &Path,
+        lines: RangeInclusive<u32>,
+    ) -> std::io::Result<Option<Vec<String>>>;
+}
+
+/// The default `SourceProvider`, which reads source files from the local
+/// filesystem
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FileSystemSource;
+
+impl SourceProvider for FileSystemSource {
+    fn source_lines(
+        &self,
+        path: &Path,
+        lines: RangeInclusive<u32>,
+    ) -> std::io::Result<Option<Vec<String>>> {
+        use std::io::BufRead;
+
+        let file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let reader = std::io::BufReader::new(file);
+        let skip = lines.start().saturating_sub(1) as usize;
+        let take = (*lines.end() as usize).saturating_sub(skip);
+
+        reader
+            .lines()
+            .skip(skip)
+            .take(take)
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+}
+
 impl Frame {
-    fn is_dependency_code(&self) -> bool {
+    /// Whether this frame was classified as the code of a dependency, rather
+    /// than your own code.
+    ///
+    /// Frames of the crates and paths configured with
+    /// `HookBuilder::add_own_crate`, `HookBuilder::add_own_path` and
+    /// `HookBuilder::workspace_members` are never dependency code. All other
+    /// frames are classified heuristically by their symbol name and path.
+    pub fn is_dependency_code(&self) -> bool {
+        self.dependency_code
+    }
+
+    /// Heuristically determine whether a frame belongs to the standard
+    /// library or a third party crate.
+    fn looks_like_dependency_code(&self) -> bool {
         const SYM_PREFIXES: &[&str] = &[
             "std::",
             "core::",
@@ -347,12 +542,31 @@ impl Frame {
             "src/libtest/",
         ];
 
+        // Directories containing the sources of registry and git dependencies,
+        // vendored crates and rustup toolchains. `*` matches any directory.
+        const DEPENDENCY_DIRS: &[&[&str]] = &[
+            &[".cargo", "registry", "src"],
+            &[".cargo", "git", "checkouts"],
+            &[".rustup", "toolchains"],
+            &["vendor", "*", "src"],
+        ];
+
         // Inspect filename.
-        if let Some(ref filename) = self.filename {
-            let filename = filename.to_string_lossy();
-            if FILE_PREFIXES.iter().any(|x| filename.starts_with(x))
-                || filename.contains("/.cargo/registry/src/")
-            {
+        if let Some(ref path) = self.filename {
+            let filename = path.to_string_lossy();
+            if FILE_PREFIXES.iter().any(|x| filename.starts_with(x)) {
+                return true;
+            }
+
+            let components: Vec<_> = path.components().map(|c| c.as_os_str()).collect();
+            if DEPENDENCY_DIRS.iter().any(|dir| {
+                components.windows(dir.len()).any(|window| {
+                    window
+                        .iter()
+                        .zip(dir.iter())
+                        .all(|(component, dir)| *dir == "*" || component == dir)
+                })
+            }) {
                 return true;
             }
         }
@@ -360,6 +574,87 @@ impl Frame {
         false
     }
 
+    /// Determine the name of the crate this frame belongs to.
+    ///
+    /// Frames of crates from the cargo registry are identified by their path,
+    /// all other frames by the first segment of their symbol name. Names are
+    /// crate identifiers as used in paths, so the package `tokio-util` is
+    /// called `tokio_util`.
+    pub(crate) fn crate_name(&self) -> Option<Cow<'_, str>> {
+        let from_registry = self.filename.as_ref().and_then(|filename| {
+            let mut components = filename.components().map(|c| c.as_os_str().to_str());
+            components.find(|c| *c == Some("registry"))?;
+            if components.next()? != Some("src") {
+                return None;
+            }
+            // Skip the registry index, e.g. `index.crates.io-6f17d22bba15001f`
+            components.next()?;
+            let krate = components.next()??;
+
+            // Strip the version, e.g. `tokio-1.32.0`
+            let version = krate
+                .match_indices('-')
+                .map(|(i, _)| i)
+                .find(|&i| krate[i + 1..].starts_with(|c: char| c.is_ascii_digit()));
+            let krate = version.map_or(krate, |i| &krate[..i]);
+            if krate.contains('-') {
+                Some(Cow::Owned(krate.replace('-', "_")))
+            } else {
+                Some(Cow::Borrowed(krate))
+            }
+        });
+
+        from_registry.or_else(|| {
+            fn first_segment(name: &str) -> Option<&str> {
+                let (krate, _) = name.trim_start_matches('<').split_once("::")?;
+                Some(krate)
+            }
+
+            let krate = match strip_crate_disambiguators(self.name.as_deref()?) {
+                Cow::Borrowed(name) => Cow::Borrowed(first_segment(name)?),
+                Cow::Owned(name) => Cow::Owned(first_segment(&name)?.to_owned()),
+            };
+            let is_ident =
+                !krate.is_empty() && krate.chars().all(|c| c.is_alphanumeric() || c == '_');
+            Some(krate).filter(|_| is_ident)
+        })
+    }
+
+    /// Heuristically determine whether a frame is part of an async executor
+    /// or one of the adapters between it and the futures it polls.
+    fn is_async_executor_code(&self) -> bool {
+        const SYM_PREFIXES: &[&str] = &[
+            "tokio::runtime::",
+            "tokio::task::",
+            "futures_executor::",
+            "futures_util::future::future::",
+            "async_std::task::",
+            "async_executor::",
+            "async_task::",
+        ];
+
+        const POLL_ADAPTERS: &[&str] = &[
+            "<core::future::from_generator::GenFuture<",
+            "<core::pin::Pin<",
+            "<core::future::poll_fn::PollFn<",
+            "<core::panic::unwind_safe::AssertUnwindSafe<",
+            "<alloc::boxed::Box<",
+            "<&mut ",
+        ];
+
+        let name = match self.name.as_ref() {
+            Some(name) => strip_crate_disambiguators(name),
+            None => return false,
+        };
+        let (name, _) = split_hash_suffix(&name);
+
+        SYM_PREFIXES
+            .iter()
+            .any(|x| name.trim_start_matches('<').starts_with(x))
+            || (POLL_ADAPTERS.iter().any(|x| name.starts_with(x))
+                && name.ends_with(" as core::future::future::Future>::poll"))
+    }
+
     /// Heuristically determine whether a frame is likely to be a post panic
     /// frame.
     ///
@@ -418,6 +713,18 @@ impl Frame {
 /// Builder for customizing the behavior of the global panic and error report hooks
 pub struct HookBuilder {
     filters: Vec<Box<FilterCallback>>,
+    source_provider: Box<dyn SourceProvider>,
+    path_remaps: Vec<(PathBuf, PathBuf)>,
+    shorten_paths: bool,
+    source_lines_before: u32,
+    source_lines_after: u32,
+    dependency_source_snippets: bool,
+    collapse_repeated_frames: bool,
+    relabel_async_frames: bool,
+    own_crates: Vec<String>,
+    own_paths: Vec<PathBuf>,
+    simplify_symbol_names: bool,
+    display_symbol_hashes: bool,
     capture_span_trace_by_default: bool,
     display_env_section: bool,
     #[cfg(feature = "track-caller")]
@@ -425,12 +732,27 @@ pub struct HookBuilder {
     panic_section: Option<Box<dyn Display + Send + Sync + 'static>>,
     panic_message: Option<Box<dyn PanicMessage>>,
     theme: Theme,
+    color_mode: ColorMode,
+    hyperlinks: Hyperlinks,
+    verbosity: Option<Verbosity>,
+    output_format: OutputFormat,
     #[cfg(feature = "issue-url")]
     issue_url: Option<String>,
     #[cfg(feature = "issue-url")]
     issue_metadata: Vec<(String, Box<dyn Display + Send + Sync + 'static>)>,
     #[cfg(feature = "issue-url")]
     issue_filter: Arc<IssueFilterCallback>,
+    capture_policy: Option<Box<CapturePolicyCallback>>,
+    panic_writers: Vec<PanicWriter>,
+    crash_report_dir: Option<PathBuf>,
+    crash_report_retention: usize,
+    crash_report_json: bool,
+    friendly_panic_message: Option<FriendlyPanicMessage>,
+    panic_callbacks_before: Vec<Box<PanicCallback>>,
+    panic_callbacks_after: Vec<Box<PanicCallback>>,
+    previous_panic_hook: PreviousPanicHook,
+    count_panics: bool,
+    panic_rate_limit: Option<(usize, Duration)>,
 }
 
 impl HookBuilder {
@@ -461,6 +783,18 @@ impl HookBuilder {
     pub fn blank() -> Self {
         HookBuilder {
             filters: vec![],
+            source_provider: Box::new(FileSystemSource),
+            path_remaps: vec![],
+            shorten_paths: false,
+            source_lines_before: 2,
+            source_lines_after: 2,
+            dependency_source_snippets: true,
+            collapse_repeated_frames: true,
+            relabel_async_frames: false,
+            own_crates: vec![],
+            own_paths: vec![],
+            simplify_symbol_names: false,
+            display_symbol_hashes: true,
             capture_span_trace_by_default: false,
             display_env_section: true,
             #[cfg(feature = "track-caller")]
@@ -468,12 +802,27 @@ impl HookBuilder {
             panic_section: None,
             panic_message: None,
             theme: Theme::dark(),
+            color_mode: ColorMode::Always,
+            hyperlinks: Hyperlinks::Disabled,
+            verbosity: None,
+            output_format: OutputFormat::Text,
             #[cfg(feature = "issue-url")]
             issue_url: None,
             #[cfg(feature = "issue-url")]
             issue_metadata: vec![],
             #[cfg(feature = "issue-url")]
             issue_filter: Arc::new(|_| true),
+            capture_policy: None,
+            panic_writers: vec![],
+            crash_report_dir: None,
+            crash_report_retention: 10,
+            crash_report_json: false,
+            friendly_panic_message: None,
+            panic_callbacks_before: vec![],
+            panic_callbacks_after: vec![],
+            previous_panic_hook: PreviousPanicHook::Replace,
+            count_panics: false,
+            panic_rate_limit: None,
         }
     }
 
@@ -485,6 +834,80 @@ impl HookBuilder {
         self
     }
 
+    /// Configures when `color_eyre` should emit colors.
+    ///
+    /// # Details
+    ///
+    /// Defaults to `ColorMode::Always`. With `ColorMode::Auto` colors are
+    /// disabled when the `NO_COLOR` environment variable is set, forced when
+    /// `CLICOLOR_FORCE` is set, and otherwise only enabled if stderr is a
+    /// terminal. The mode is evaluated once, when the hooks are created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{ColorMode, HookBuilder};
+    ///
+    /// HookBuilder::default()
+    ///     .color_mode(ColorMode::Auto)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self
+    }
+
+    /// Configures whether source locations are rendered as terminal
+    /// hyperlinks.
+    ///
+    /// # Details
+    ///
+    /// When enabled, the `file:line` locations of backtrace frames and of the
+    /// `Location:` sections of error and panic reports are wrapped in OSC 8
+    /// escape sequences, which supporting terminals render as clickable links.
+    /// Hyperlinks are never emitted if colors are disabled via `color_mode`,
+    /// and terminals without OSC 8 support display the location as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, Hyperlinks};
+    ///
+    /// HookBuilder::default()
+    ///     .hyperlinks(Hyperlinks::Editor("vscode://file/{path}:{line}:{col}".into()))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn hyperlinks(mut self, hyperlinks: Hyperlinks) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// Set the format used to render error and panic reports.
+    ///
+    /// # Details
+    ///
+    /// `OutputFormat::Json` renders each report as a single JSON document
+    /// instead of themed text, which is useful when stderr is consumed by a
+    /// log pipeline. The `COLORBT_FORMAT` environment variable (`json` or
+    /// `text`) takes precedence over this setting when it is present.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, OutputFormat};
+    ///
+    /// HookBuilder::default()
+    ///     .output_format(OutputFormat::Json)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
     /// Add a custom section to the panic hook that will be printed
     /// in the panic message.
     ///
@@ -558,6 +981,244 @@ impl HookBuilder {
         self
     }
 
+    /// Show end users a short apology instead of the developer oriented
+    /// panic report
+    ///
+    /// # Details
+    ///
+    /// Unless a backtrace is requested, e.g. via `RUST_BACKTRACE=1`, panic
+    /// reports only consist of the `FriendlyPanicMessage`, the panic section
+    /// and, if a crash report directory was set with
+    /// `HookBuilder::crash_report_dir`, the path of the saved crash report.
+    /// The panic payload, location, span trace and backtrace are left out. If
+    /// a backtrace is requested, the full report is printed as usual so
+    /// developers still get all the details.
+    ///
+    /// Reports in the `OutputFormat::Json` format aren't affected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{FriendlyPanicMessage, HookBuilder};
+    ///
+    /// HookBuilder::default()
+    ///     .friendly_panics(
+    ///         FriendlyPanicMessage::new(env!("CARGO_PKG_NAME"))
+    ///             .version(env!("CARGO_PKG_VERSION"))
+    ///             .support_url("https://github.com/eyre-rs/color-eyre/issues"),
+    ///     )
+    ///     .crash_report_dir(std::env::temp_dir().join("my-app"))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn friendly_panics(mut self, message: FriendlyPanicMessage) -> Self {
+        self.friendly_panic_message = Some(message);
+        self
+    }
+
+    /// Add a callback that runs before a panic report is printed
+    ///
+    /// # Details
+    ///
+    /// Callbacks run in the order they were added, e.g. to leave the
+    /// alternate screen and disable raw mode so the report is readable and
+    /// the terminal usable afterwards. They are only run by the hook
+    /// installed with `install` or created with `PanicHook::into_panic_hook`.
+    ///
+    /// A panic inside a callback aborts the process without printing another
+    /// report. Panics in other threads run the callbacks as usual, so they
+    /// may run concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::HookBuilder;
+    ///
+    /// HookBuilder::default()
+    ///     .on_panic_before(|_| {
+    ///         // Leave the alternate screen and show the cursor again
+    ///         eprint!("\x1b[?1049l\x1b[?25h");
+    ///     })
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    #[allow(deprecated)]
+    pub fn on_panic_before<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&std::panic::PanicInfo<'_>) + Send + Sync + 'static,
+    {
+        self.panic_callbacks_before.push(Box::new(callback));
+        self
+    }
+
+    /// Add a callback that runs after a panic report was printed, e.g. to
+    /// flush buffered logs before the process exits
+    ///
+    /// The same guarantees as for `on_panic_before` apply.
+    #[allow(deprecated)]
+    pub fn on_panic_after<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&std::panic::PanicInfo<'_>) + Send + Sync + 'static,
+    {
+        self.panic_callbacks_after.push(Box::new(callback));
+        self
+    }
+
+    /// Configures what happens to the panic hook that was installed before
+    /// `PanicHook::install` is called
+    ///
+    /// # Details
+    ///
+    /// Defaults to `PreviousPanicHook::Replace`. When chaining, the previous
+    /// hook is taken with `std::panic::take_hook`, so if no hook was
+    /// installed before, the standard library's default hook is called,
+    /// which prints its own panic message. Callbacks added with
+    /// `on_panic_before` and `on_panic_after` run before and after the
+    /// previous hook, respectively.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, PreviousPanicHook};
+    ///
+    /// std::panic::set_hook(Box::new(|_| {
+    ///     // e.g. count panics for metrics
+    /// }));
+    ///
+    /// HookBuilder::default()
+    ///     .previous_panic_hook(PreviousPanicHook::CallAfter)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn previous_panic_hook(mut self, previous: PreviousPanicHook) -> Self {
+        self.previous_panic_hook = previous;
+        self
+    }
+
+    /// Configures whether panic reports include the number of the panic
+    /// within this process, e.g. `Thread:   'worker-7', panic 3 of this
+    /// process`
+    ///
+    /// Defaults to `false`. Panics are counted by the installed panic hook,
+    /// reports built with `PanicHook::panic_report` show the number of the
+    /// latest counted panic.
+    pub fn count_panics(mut self, cond: bool) -> Self {
+        self.count_panics = cond;
+        self
+    }
+
+    /// Limit how often identical panics are fully reported
+    ///
+    /// # Details
+    ///
+    /// Panics are identical if they have the same message and location. Only
+    /// the first `max_reports` identical panics within `period` are fully
+    /// reported, further ones only print a single line until the period has
+    /// passed. No crash reports are saved for them. This keeps a flood of
+    /// panics in worker threads from burying everything else.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::HookBuilder;
+    /// use std::time::Duration;
+    ///
+    /// HookBuilder::default()
+    ///     .panic_rate_limit(3, Duration::from_secs(60))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn panic_rate_limit(mut self, max_reports: usize, period: Duration) -> Self {
+        self.panic_rate_limit = Some((max_reports, period));
+        self
+    }
+
+    /// Add a destination for panic reports
+    ///
+    /// # Details
+    ///
+    /// By default panic reports are printed to stderr. Once a `PanicWriter`
+    /// is added, reports are written to each of the added writers instead,
+    /// so add `PanicWriter::new(std::io::stderr)` to keep printing them to
+    /// stderr as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, PanicWriter};
+    /// use std::fs::OpenOptions;
+    ///
+    /// HookBuilder::default()
+    ///     .panic_writer(PanicWriter::new(std::io::stderr))
+    ///     .panic_writer(
+    ///         PanicWriter::try_new(|| {
+    ///             OpenOptions::new()
+    ///                 .create(true)
+    ///                 .append(true)
+    ///                 .open("panics.log")
+    ///         })
+    ///         .colored(false),
+    ///     )
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn panic_writer(mut self, writer: PanicWriter) -> Self {
+        self.panic_writers.push(writer);
+        self
+    }
+
+    /// Save a full, uncolored copy of every panic report to a file in `dir`
+    ///
+    /// # Details
+    ///
+    /// Reports are saved as `panic-<timestamp>-<pid>.txt`, with the UTC time
+    /// of the panic and the id of the process, and always include the
+    /// backtrace, even if `RUST_BACKTRACE` isn't set. The report printed to
+    /// the console is unchanged apart from an additional line pointing to the
+    /// saved file. To only show end users a short message, combine this with
+    /// `HookBuilder::friendly_panics`. The directory is created if it doesn't
+    /// exist.
+    ///
+    /// Reports are only saved by the installed panic hook. Custom hooks built
+    /// around `PanicHook::panic_report` can save them with
+    /// `PanicHook::save_crash_report`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::HookBuilder;
+    ///
+    /// HookBuilder::default()
+    ///     .crash_report_dir(std::env::temp_dir().join("my-app"))
+    ///     .crash_report_retention(5)
+    ///     .crash_report_json(true)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn crash_report_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.crash_report_dir = Some(dir.into());
+        self
+    }
+
+    /// Configures how many crash reports are kept in the directory set with
+    /// `crash_report_dir`. The oldest reports are deleted whenever a new
+    /// report is saved.
+    ///
+    /// Defaults to 10. The report that was just saved is always kept.
+    pub fn crash_report_retention(mut self, max_reports: usize) -> Self {
+        self.crash_report_retention = max_reports;
+        self
+    }
+
+    /// Configures whether a `.json` copy of every crash report, in the
+    /// format of `OutputFormat::Json`, is saved next to the text report.
+    ///
+    /// Defaults to `false`.
+    pub fn crash_report_json(mut self, cond: bool) -> Self {
+        self.crash_report_json = cond;
+        self
+    }
+
     /// Set an upstream github repo and enable issue reporting url generation
     ///
     /// # Details
@@ -643,12 +1304,100 @@ impl HookBuilder {
         self
     }
 
+    /// Configures a callback deciding whether a backtrace and span trace are
+    /// captured for an error report
+    ///
+    /// # Details
+    ///
+    /// The callback is called with the error whenever a report is created and
+    /// can only prevent capturing, e.g. to avoid the cost of capturing a
+    /// backtrace for frequent, expected errors. A backtrace is still only
+    /// captured if the verbosity isn't `Verbosity::Minimal` and a span trace
+    /// only if span trace capture is enabled. Panics are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{CapturePolicy, HookBuilder};
+    ///
+    /// HookBuilder::default()
+    ///     .capture_policy(|error| {
+    ///         let would_block = error
+    ///             .downcast_ref::<std::io::Error>()
+    ///             .is_some_and(|error| error.kind() == std::io::ErrorKind::WouldBlock);
+    ///
+    ///         CapturePolicy {
+    ///             backtrace: !would_block,
+    ///             span_trace: !would_block,
+    ///         }
+    ///     })
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn capture_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&(dyn std::error::Error + 'static)) -> CapturePolicy + Send + Sync + 'static,
+    {
+        self.capture_policy = Some(Box::new(policy));
+        self
+    }
+
     /// Configures the default capture mode for `SpanTraces` in error reports and panics
     pub fn capture_span_trace_by_default(mut self, cond: bool) -> Self {
         self.capture_span_trace_by_default = cond;
         self
     }
 
+    /// Set the verbosity of error and panic reports, overriding the
+    /// `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment variables.
+    ///
+    /// # Details
+    ///
+    /// The verbosity is set globally once `install` installed the hooks. It
+    /// can be changed later with `set_verbosity`, which is also how to set it
+    /// when installing the hooks created by `into_hooks` yourself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, Verbosity};
+    ///
+    /// HookBuilder::default()
+    ///     .verbosity(Verbosity::Medium)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = Some(verbosity);
+        self
+    }
+
+    /// Configures whether symbol names in backtraces are simplified.
+    ///
+    /// # Details
+    ///
+    /// When enabled, generic parameter lists are collapsed to `<…>`, paths of
+    /// types and traits from `std`, `core` and `alloc` are shortened to their
+    /// name and chains of nested closures are collapsed into one. For example
+    /// `<alloc::boxed::Box<F,A> as core::ops::function::FnOnce<Args>>::call_once`
+    /// is displayed as `<Box<…> as FnOnce<…>>::call_once`.
+    ///
+    /// Full names are still displayed when running with
+    /// `COLORBT_SHOW_HIDDEN=1` and in the JSON output format.
+    pub fn simplify_symbol_names(mut self, cond: bool) -> Self {
+        self.simplify_symbol_names = cond;
+        self
+    }
+
+    /// Configures whether the hash suffix of symbol names, e.g.
+    /// `::h1a2b3c4d5e6f7a8b`, is displayed in backtraces.
+    ///
+    /// Hashes are always displayed when running with `COLORBT_SHOW_HIDDEN=1`.
+    pub fn display_symbol_hashes(mut self, cond: bool) -> Self {
+        self.display_symbol_hashes = cond;
+        self
+    }
+
     /// Configures the enviroment varible info section and whether or not it is displayed
     pub fn display_env_section(mut self, cond: bool) -> Self {
         self.display_env_section = cond;
@@ -667,8 +1416,161 @@ impl HookBuilder {
         self
     }
 
+    /// Set the `SourceProvider` used to look up the source snippets displayed
+    /// for each frame when `RUST_BACKTRACE=full` is set.
+    ///
+    /// Defaults to `FileSystemSource`.
+    pub fn source_provider<P: SourceProvider>(mut self, provider: P) -> Self {
+        self.source_provider = Box::new(provider);
+        self
+    }
+
+    /// Configures how many lines of source are displayed before and after the
+    /// active line of each frame's source snippet.
+    ///
+    /// Source snippets are only displayed when `RUST_BACKTRACE=full` is set.
+    /// Defaults to 2 lines before and 2 lines after.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// color_eyre::config::HookBuilder::default()
+    ///     .source_context(1, 3)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn source_context(mut self, before: u32, after: u32) -> Self {
+        self.source_lines_before = before;
+        self.source_lines_after = after;
+        self
+    }
+
+    /// Configures whether source snippets are displayed for frames that are
+    /// not part of your crate, e.g. frames of `std` or dependencies.
+    ///
+    /// Defaults to `true`. Disabling this keeps backtraces with deep stacks of
+    /// library frames readable when running with `RUST_BACKTRACE=full`.
+    pub fn dependency_source_snippets(mut self, cond: bool) -> Self {
+        self.dependency_source_snippets = cond;
+        self
+    }
+
+    /// Configures whether repeated frames are collapsed in backtraces.
+    ///
+    /// # Details
+    ///
+    /// When enabled, which is the default, consecutive repetitions of the same
+    /// frame or of the same cycle of frames, as produced by deep recursion,
+    /// are displayed once, followed by a single line summarizing the repeated
+    /// frames. Running with `COLORBT_SHOW_HIDDEN=1` displays every frame.
+    pub fn collapse_repeated_frames(mut self, cond: bool) -> Self {
+        self.collapse_repeated_frames = cond;
+        self
+    }
+
+    /// Treat the frames of the crate called `name` as your own code.
+    ///
+    /// # Details
+    ///
+    /// `name` is the crate name as used in paths, e.g. `my_lib` for the
+    /// package `my-lib`.
+    ///
+    /// Frames of your own code are styled with `Theme::crate_code` and are
+    /// never hidden by the default filters. By default, frames are considered
+    /// dependency code based on their symbol name and on whether their source
+    /// lives in the cargo registry, a cargo git checkout, a crate in a
+    /// `vendor` directory or a rustup toolchain.
+    pub fn add_own_crate(mut self, name: impl Into<String>) -> Self {
+        self.own_crates.push(name.into());
+        self
+    }
+
+    /// Treat the frames whose source lives inside `root` as your own code,
+    /// except for sources in `root/vendor` and in the registry and git
+    /// checkouts of a `.cargo` directory below it.
+    ///
+    /// See `add_own_crate` for details.
+    pub fn add_own_path(mut self, root: impl Into<PathBuf>) -> Self {
+        self.own_paths.push(root.into());
+        self
+    }
+
+    /// Treat the frames of the members of your cargo workspace as your own
+    /// code.
+    ///
+    /// # Details
+    ///
+    /// `members` is the list of package directories that
+    /// `color_eyre::workspace::detect_workspace` detects at build time, when
+    /// it is called from your build script. It is passed to the crate as the
+    /// `COLOR_EYRE_WORKSPACE_MEMBERS` environment variable, so embed it with
+    /// `env!`. Each directory is added with `add_own_path`.
+    ///
+    /// Only the build script needs the `detect-workspace` feature of
+    /// `color-eyre`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// color_eyre::config::HookBuilder::default()
+    ///     .workspace_members(env!("COLOR_EYRE_WORKSPACE_MEMBERS"))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn workspace_members(self, members: &str) -> Self {
+        std::env::split_paths(members).fold(self, Self::add_own_path)
+    }
+
+    /// Add a rule that rewrites source paths starting with `from` to start
+    /// with `to` instead.
+    ///
+    /// # Details
+    ///
+    /// This is the counterpart to rustc's `--remap-path-prefix` flag, and to
+    /// binaries built in a different directory than the one they are debugged
+    /// in. Rules are tried in the order they were added and the first matching
+    /// rule is applied. Remapped paths are used for displaying frames and
+    /// locations, for looking up source snippets, and in JSON and issue url
+    /// output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// color_eyre::config::HookBuilder::default()
+    ///     .remap_path_prefix("/build/my-app", "/home/me/src/my-app")
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn remap_path_prefix(mut self, from: impl Into<PathBuf>, to: impl Into<PathBuf>) -> Self {
+        self.path_remaps.push((from.into(), to.into()));
+        self
+    }
+
+    /// Configures whether displayed paths are shortened.
+    ///
+    /// When enabled, sources of crates from the cargo registry are displayed
+    /// as `crate-x.y.z/src/lib.rs` and the home directory is displayed as `~`.
+    /// This only affects how paths are displayed in human readable reports.
+    pub fn shorten_paths(mut self, cond: bool) -> Self {
+        self.shorten_paths = cond;
+        self
+    }
+
     /// Add a custom filter to the set of frame filters
     ///
+    /// # Details
+    ///
+    /// Filters run in the order they were added. Afterwards the comma
+    /// separated rules of the `COLORBT_FILTER` environment variable are
+    /// applied, e.g. `COLORBT_FILTER=-tokio::,-hyper::,+mycrate::`. Rules
+    /// starting with `-` hide matching frames and rules starting with `+` show
+    /// them, even if a filter hid them. Patterns containing `/` or `\` are
+    /// globs matched against the frame's file path, e.g. `-*/vendor/*`. Other
+    /// patterns containing `*` or `?` are globs matched against the frame's
+    /// symbol name, e.g. `-tokio::*`, and all remaining patterns match the
+    /// start of the symbol name. When several rules match a frame, the last
+    /// one wins.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -700,9 +1602,15 @@ impl HookBuilder {
 
     /// Install the given Hook as the global error report hook
     pub fn install(self) -> Result<(), crate::eyre::Report> {
+        let verbosity = self.verbosity;
         let (panic_hook, eyre_hook) = self.try_into_hooks()?;
         eyre_hook.install()?;
         panic_hook.install();
+
+        if verbosity.is_some() {
+            set_verbosity(verbosity);
+        }
+
         Ok(())
     }
 
@@ -712,6 +1620,34 @@ impl HookBuilder {
             .add_frame_filter(Box::new(eyre_frame_filters))
     }
 
+    /// Add a set of filters for backtraces captured inside async code to this
+    /// `HookBuilder`'s configuration
+    ///
+    /// # Details
+    ///
+    /// This hides the frames of the runtimes and task machinery of async
+    /// executors such as `tokio`, `futures` and `async-std`, as well as the
+    /// `Future::poll` adapters between them and the polled futures. Frames of
+    /// the rest of these crates, e.g. `tokio::fs` or `tokio::sync`, are kept.
+    ///
+    /// Additionally the `{{closure}}` frames of async fn bodies are relabeled
+    /// with the name of their async fn, so that the remaining frames read like
+    /// the logical call stack. Whether a closure is the body of an async fn is
+    /// determined heuristically from the frame that polls it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// color_eyre::config::HookBuilder::default()
+    ///     .add_async_frame_filters()
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn add_async_frame_filters(mut self) -> Self {
+        self.relabel_async_frames = true;
+        self.add_frame_filter(Box::new(async_frame_filters))
+    }
+
     /// Create a `PanicHook` and `EyreHook` from this `HookBuilder`.
     /// This can be used if you want to combine these handlers with other handlers.
     pub fn into_hooks(self) -> (PanicHook, EyreHook) {
@@ -721,19 +1657,68 @@ impl HookBuilder {
     /// Create a `PanicHook` and `EyreHook` from this `HookBuilder`.
     /// This can be used if you want to combine these handlers with other handlers.
     pub fn try_into_hooks(self) -> Result<(PanicHook, EyreHook), crate::eyre::Report> {
-        let theme = self.theme;
+        let colors_enabled = self.color_mode.colors_enabled();
+        let theme = if colors_enabled {
+            self.theme
+        } else {
+            Theme::new()
+        };
         #[cfg(feature = "issue-url")]
         let metadata = Arc::new(self.issue_metadata);
+        let path_remapper = PathRemapper::new(self.path_remaps, self.shorten_paths);
+        let own_paths = self
+            .own_paths
+            .iter()
+            .map(|path| path_remapper.remap(path).into_owned())
+            .collect();
+        let frame_settings = Arc::new(FrameSettings {
+            source_provider: self.source_provider,
+            path_remapper,
+            source_lines_before: self.source_lines_before,
+            source_lines_after: self.source_lines_after,
+            dependency_source_snippets: self.dependency_source_snippets,
+            collapse_repeated_frames: self.collapse_repeated_frames,
+            relabel_async_frames: self.relabel_async_frames,
+            own_crates: self.own_crates,
+            own_paths,
+            simplify_symbol_names: self.simplify_symbol_names,
+            display_symbol_hashes: self.display_symbol_hashes,
+            hyperlinks: if colors_enabled {
+                self.hyperlinks
+            } else {
+                Hyperlinks::Disabled
+            },
+        });
+        let (retention, json) = (self.crash_report_retention, self.crash_report_json);
+        let crash_report_dir = self.crash_report_dir.map(|dir| CrashReportDir {
+            dir,
+            retention,
+            json,
+        });
         let panic_hook = PanicHook {
+            writers: self.panic_writers,
+            crash_report_dir,
+            friendly_panic_message: self.friendly_panic_message,
+            callbacks_before: self.panic_callbacks_before,
+            callbacks_after: self.panic_callbacks_after,
+            previous_hook: self.previous_panic_hook,
+            count_panics: self.count_panics,
+            rate_limit: self
+                .panic_rate_limit
+                .map(|(max_reports, period)| PanicRateLimit {
+                    max_reports,
+                    period,
+                    panics: Mutex::new(HashMap::new()),
+                }),
             filters: self.filters.into(),
+            frame_settings: frame_settings.clone(),
             section: self.panic_section,
             #[cfg(feature = "capture-spantrace")]
             capture_span_trace_by_default: self.capture_span_trace_by_default,
             display_env_section: self.display_env_section,
-            panic_message: self
-                .panic_message
-                .unwrap_or_else(|| Box::new(DefaultPanicMessage(theme))),
+            panic_message: self.panic_message,
             theme,
+            output_format: self.output_format,
             #[cfg(feature = "issue-url")]
             issue_url: self.issue_url.clone(),
             #[cfg(feature = "issue-url")]
@@ -744,22 +1729,25 @@ impl HookBuilder {
 
         let eyre_hook = EyreHook {
             filters: panic_hook.filters.clone(),
+            frame_settings,
             #[cfg(feature = "capture-spantrace")]
             capture_span_trace_by_default: self.capture_span_trace_by_default,
             display_env_section: self.display_env_section,
             #[cfg(feature = "track-caller")]
             display_location_section: self.display_location_section,
             theme,
+            output_format: self.output_format,
             #[cfg(feature = "issue-url")]
             issue_url: self.issue_url,
             #[cfg(feature = "issue-url")]
             issue_metadata: metadata,
             #[cfg(feature = "issue-url")]
             issue_filter: self.issue_filter,
+            capture_policy: self.capture_policy,
         };
 
         #[cfg(feature = "capture-spantrace")]
-        eyre::WrapErr::wrap_err(color_spantrace::set_theme(self.theme.into()), "could not set the provided `Theme` via `color_spantrace::set_theme` globally as another was already set")?;
+        eyre::WrapErr::wrap_err(color_spantrace::set_theme(theme.into()), "could not set the provided `Theme` via `color_spantrace::set_theme` globally as another was already set")?;
 
         Ok((panic_hook, eyre_hook))
     }
@@ -787,19 +1775,23 @@ impl Default for HookBuilder {
 fn default_frame_filter(frames: &mut Vec<&Frame>) {
     let top_cutoff = frames
         .iter()
-        .rposition(|x| x.is_post_panic_code())
+        .rposition(|x| !x.own_code && x.is_post_panic_code())
         .map(|x| x + 2) // indices are 1 based
         .unwrap_or(0);
 
     let bottom_cutoff = frames
         .iter()
-        .position(|x| x.is_runtime_init_code())
+        .position(|x| !x.own_code && x.is_runtime_init_code())
         .unwrap_or(frames.len());
 
     let rng = top_cutoff..=bottom_cutoff;
     frames.retain(|x| rng.contains(&x.n))
 }
 
+fn async_frame_filters(frames: &mut Vec<&Frame>) {
+    frames.retain(|frame| !frame.is_async_executor_code());
+}
+
 fn eyre_frame_filters(frames: &mut Vec<&Frame>) {
     let filters = &[
         "<color_eyre::Handler as eyre::EyreHandler>::default",
@@ -808,65 +1800,181 @@ fn eyre_frame_filters(frames: &mut Vec<&Frame>) {
     ];
 
     frames.retain(|frame| {
-        !filters.iter().any(|f| {
-            let name = if let Some(name) = frame.name.as_ref() {
-                name.as_str()
-            } else {
-                return true;
-            };
-
-            name.starts_with(f)
-        })
+        frame.own_code
+            || !filters.iter().any(|f| {
+                let name = if let Some(name) = frame.name.as_ref() {
+                    name.as_str()
+                } else {
+                    return true;
+                };
+
+                name.starts_with(f)
+            })
     });
 }
 
-struct DefaultPanicMessage(Theme);
+/// The message printed at the start of panic reports unless it was replaced
+/// with `HookBuilder::panic_message`
+fn print_default_panic_message(
+    report: &PanicReport<'_>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let pi = report.panic_info;
+    let theme = &report.hook.theme;
+
+    writeln!(
+        f,
+        "{}",
+        "The application panicked (crashed).".style(theme.panic_header)
+    )?;
+
+    // Print panic message.
+    let payload = panic_payload(pi);
+
+    write!(f, "Message:  ")?;
+    writeln!(f, "{}", payload.style(theme.panic_message))?;
+
+    // If known, print panic location.
+    write!(f, "Location: ")?;
+    write!(
+        f,
+        "{}",
+        crate::fmt::LocationSection(pi.location(), *theme, &report.hook.frame_settings)
+    )?;
+
+    write!(f, "\nThread:   {}", thread_label(&report.thread))?;
+    if report.hook.count_panics {
+        write!(f, ", panic {} of this process", report.panic_number)?;
+    }
 
-impl PanicMessage for DefaultPanicMessage {
-    fn display(&self, pi: &std::panic::PanicInfo<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // XXX is my assumption correct that this function is guaranteed to only run after `color_eyre` was setup successfully (including setting `THEME`), and that therefore the following line will never panic? Otherwise, we could return `fmt::Error`, but if the above is true, I like `unwrap` + a comment why this never fails better
-        let theme = &self.0;
+    Ok(())
+}
 
-        writeln!(
-            f,
-            "{}",
-            "The application panicked (crashed).".style(theme.panic_header)
-        )?;
+/// A short panic message for the end users of an application, which doesn't
+/// mention any implementation details
+///
+/// It can be used as a `PanicMessage` or, to also hide the rest of the
+/// developer oriented report, with `HookBuilder::friendly_panics`.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::config::FriendlyPanicMessage;
+///
+/// let message = FriendlyPanicMessage::new("my-app")
+///     .version("1.2.3")
+///     .support_url("https://github.com/me/my-app/issues");
+/// ```
+///
+/// Panic reports then start with
+///
+/// ```text
+/// my-app 1.2.3 crashed unexpectedly, sorry about that!
+/// Please report this problem at https://github.com/me/my-app/issues
+/// ```
+#[derive(Debug, Clone)]
+pub struct FriendlyPanicMessage {
+    name: String,
+    version: Option<String>,
+    support_url: Option<String>,
+}
+
+impl FriendlyPanicMessage {
+    /// Construct a message for the application called `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        FriendlyPanicMessage {
+            name: name.into(),
+            version: None,
+            support_url: None,
+        }
+    }
+
+    /// Set the version of the application shown after its name
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
 
-        // Print panic message.
-        let payload = pi
-            .payload()
-            .downcast_ref::<String>()
-            .map(String::as_str)
-            .or_else(|| pi.payload().downcast_ref::<&str>().cloned())
-            .unwrap_or("<non string panic payload>");
+    /// Set the URL where users can report the problem
+    pub fn support_url(mut self, url: impl Into<String>) -> Self {
+        self.support_url = Some(url.into());
+        self
+    }
+}
 
-        write!(f, "Message:  ")?;
-        writeln!(f, "{}", payload.style(theme.panic_message))?;
+#[allow(deprecated)]
+impl PanicMessage for FriendlyPanicMessage {
+    fn display(&self, _pi: &std::panic::PanicInfo<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        write!(f, " crashed unexpectedly, sorry about that!")?;
 
-        // If known, print panic location.
-        write!(f, "Location: ")?;
-        write!(f, "{}", crate::fmt::LocationSection(pi.location(), *theme))?;
+        if let Some(url) = &self.support_url {
+            write!(f, "\nPlease report this problem at {}", url)?;
+        }
 
         Ok(())
     }
 }
 
 /// A type representing an error report for a panic.
+#[allow(deprecated)]
 pub struct PanicReport<'a> {
     hook: &'a PanicHook,
     panic_info: &'a std::panic::PanicInfo<'a>,
     backtrace: Option<backtrace::Backtrace>,
     #[cfg(feature = "capture-spantrace")]
     span_trace: Option<tracing_error::SpanTrace>,
+    crash_report: Option<std::io::Result<PathBuf>>,
+    thread: std::thread::Thread,
+    panic_number: usize,
+}
+
+/// The contents of a crash report file for a `PanicReport`
+struct CrashReport<'a>(&'a PanicReport<'a>, OutputFormat);
+
+impl fmt::Display for CrashReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            OutputFormat::Json => print_panic_info_json(self.0, true, f),
+            OutputFormat::Text => print_panic_info_text(self.0, true, f),
+        }
+    }
 }
 
 fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    report.hook.panic_message.display(report.panic_info, f)?;
+    match report.hook.output_format.resolve() {
+        OutputFormat::Json => print_panic_info_json(report, false, f),
+        OutputFormat::Text => print_panic_info_text(report, false, f),
+    }
+}
 
+/// Print the text version of a panic report. Crash report files always
+/// include the backtrace, but not the environment variable hints.
+fn print_panic_info_text(
+    report: &PanicReport<'_>,
+    is_crash_report: bool,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
     let v = panic_verbosity();
     let capture_bt = v != Verbosity::Minimal;
 
+    let friendly_panic_message = report
+        .hook
+        .friendly_panic_message
+        .as_ref()
+        .filter(|_| !capture_bt && !is_crash_report);
+    if let Some(message) = friendly_panic_message {
+        return print_friendly_panic_info(report, message, f);
+    }
+
+    match &report.hook.panic_message {
+        Some(message) => message.display(report.panic_info, f)?,
+        None => print_default_panic_message(report, f)?,
+    }
+
     let mut separated = f.header("\n\n");
 
     if let Some(ref section) = report.hook.section {
@@ -884,7 +1992,11 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
         }
     }
 
-    if let Some(bt) = report.backtrace.as_ref() {
+    if let Some(bt) = report
+        .backtrace
+        .as_ref()
+        .filter(|_| capture_bt || is_crash_report)
+    {
         let fmted_bt = report.hook.format_backtrace(bt);
         write!(
             indented(&mut separated.ready()).with_format(Format::Uniform { indentation: "  " }),
@@ -893,9 +2005,14 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
         )?;
     }
 
-    if report.hook.display_env_section {
+    if !is_crash_report {
+        write_crash_report_note(report, &mut separated.ready())?;
+    }
+
+    if report.hook.display_env_section && !is_crash_report {
         let env_section = EnvSection {
             bt_captured: &capture_bt,
+            verbosity: panic_verbosity(),
             #[cfg(feature = "capture-spantrace")]
             span_trace: report.span_trace.as_ref(),
         };
@@ -907,20 +2024,19 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
     {
         let payload = report.panic_info.payload();
 
-        if report.hook.issue_url.is_some()
-            && (*report.hook.issue_filter)(crate::ErrorKind::NonRecoverable(payload))
+        if let Some(url) = report
+            .hook
+            .issue_url
+            .as_ref()
+            .filter(|_| (*report.hook.issue_filter)(crate::ErrorKind::NonRecoverable(payload)))
         {
-            let url = report.hook.issue_url.as_ref().unwrap();
-            let payload = payload
-                .downcast_ref::<String>()
-                .map(String::as_str)
-                .or_else(|| payload.downcast_ref::<&str>().cloned())
-                .unwrap_or("<non string panic payload>");
+            let payload = panic_payload(report.panic_info);
 
             let issue_section = crate::section::github::IssueSection::new(url, payload)
                 .with_backtrace(report.backtrace.as_ref())
                 .with_location(report.panic_info.location())
-                .with_metadata(&**report.hook.issue_metadata);
+                .with_path_remapper(&report.hook.frame_settings.path_remapper)
+                .with_metadata(&report.hook.issue_metadata);
 
             #[cfg(feature = "capture-spantrace")]
             let issue_section = issue_section.with_span_trace(report.span_trace.as_ref());
@@ -932,6 +2048,114 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
     Ok(())
 }
 
+/// Print the end user facing report of `HookBuilder::friendly_panics`
+fn print_friendly_panic_info(
+    report: &PanicReport<'_>,
+    message: &FriendlyPanicMessage,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    message.display(report.panic_info, f)?;
+
+    let mut separated = f.header("\n\n");
+
+    if let Some(ref section) = report.hook.section {
+        write!(&mut separated.ready(), "{}", section)?;
+    }
+
+    write_crash_report_note(report, &mut separated.ready())
+}
+
+/// Point to the crash report file saved for `report`, if any
+fn write_crash_report_note(report: &PanicReport<'_>, f: &mut impl fmt::Write) -> fmt::Result {
+    let theme = report.hook.theme;
+    match (&report.crash_report, &report.hook.crash_report_dir) {
+        (Some(Ok(path)), _) => write!(
+            f,
+            "Full report saved to {}",
+            path.display().style(theme.panic_file)
+        ),
+        (Some(Err(error)), Some(crash_report_dir)) => write!(
+            f,
+            "Failed to save the full report to {}: {}",
+            crash_report_dir.dir.display().style(theme.panic_file),
+            error
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn print_panic_info_json(
+    report: &PanicReport<'_>,
+    is_crash_report: bool,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    use crate::json;
+
+    let payload = panic_payload(report.panic_info);
+
+    let mut object = json::Object::new(f)?;
+    object.field("kind", json::Str("panic"))?;
+    object.field("message", json::Str(payload))?;
+    object.field(
+        "location",
+        json::Nullable(
+            report
+                .panic_info
+                .location()
+                .map(|loc| json::Location(loc, &report.hook.frame_settings.path_remapper)),
+        ),
+    )?;
+
+    object.field(
+        "section",
+        json::Nullable(report.hook.section.as_ref().map(json::Str)),
+    )?;
+
+    #[cfg(feature = "capture-spantrace")]
+    object.field(
+        "span_trace",
+        json::Nullable(
+            report.span_trace.as_ref().map(|span_trace| {
+                json::Spans(span_trace, &report.hook.frame_settings.path_remapper)
+            }),
+        ),
+    )?;
+
+    let capture_bt = panic_verbosity() != Verbosity::Minimal;
+    match report
+        .backtrace
+        .as_ref()
+        .filter(|_| capture_bt || is_crash_report)
+    {
+        Some(bt) => {
+            let fmted_bt = report.hook.format_backtrace(bt);
+            let frames = fmted_bt.frames();
+            let filtered_frames = fmted_bt.filter(&frames);
+            object.field("backtrace", json::Frames(&filtered_frames))?;
+        }
+        None => object.field("backtrace", "null")?,
+    }
+
+    object.field(
+        "thread",
+        json::Str(
+            report
+                .thread
+                .name()
+                .map_or_else(|| format!("{:?}", report.thread.id()), str::to_owned),
+        ),
+    )?;
+    if report.hook.count_panics {
+        object.field("panic_number", report.panic_number)?;
+    }
+
+    if let Some(Ok(path)) = report.crash_report.as_ref().filter(|_| !is_crash_report) {
+        object.field("crash_report", json::Str(path.display()))?;
+    }
+
+    object.finish()
+}
+
 impl fmt::Display for PanicReport<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         print_panic_info(self, f)
@@ -941,9 +2165,11 @@ impl fmt::Display for PanicReport<'_> {
 /// A panic reporting hook
 pub struct PanicHook {
     filters: Arc<[Box<FilterCallback>]>,
+    frame_settings: Arc<FrameSettings>,
     section: Option<Box<dyn Display + Send + Sync + 'static>>,
-    panic_message: Box<dyn PanicMessage>,
+    panic_message: Option<Box<dyn PanicMessage>>,
     theme: Theme,
+    output_format: OutputFormat,
     #[cfg(feature = "capture-spantrace")]
     capture_span_trace_by_default: bool,
     display_env_section: bool,
@@ -953,8 +2179,17 @@ pub struct PanicHook {
     issue_metadata: Arc<Vec<(String, Box<dyn Display + Send + Sync + 'static>)>>,
     #[cfg(feature = "issue-url")]
     issue_filter: Arc<IssueFilterCallback>,
+    writers: Vec<PanicWriter>,
+    crash_report_dir: Option<CrashReportDir>,
+    friendly_panic_message: Option<FriendlyPanicMessage>,
+    callbacks_before: Vec<Box<PanicCallback>>,
+    callbacks_after: Vec<Box<PanicCallback>>,
+    previous_hook: PreviousPanicHook,
+    count_panics: bool,
+    rate_limit: Option<PanicRateLimit>,
 }
 
+#[allow(deprecated)]
 impl PanicHook {
     pub(crate) fn format_backtrace<'a>(
         &'a self,
@@ -962,8 +2197,10 @@ impl PanicHook {
     ) -> BacktraceFormatter<'a> {
         BacktraceFormatter {
             filters: &self.filters,
+            frame_settings: &self.frame_settings,
             inner: trace,
             theme: self.theme,
+            verbosity: panic_verbosity(),
         }
     }
 
@@ -975,27 +2212,121 @@ impl PanicHook {
     }
 
     /// Install self as a global panic hook via `std::panic::set_hook`.
+    ///
+    /// The previously installed hook is replaced or chained to, as configured
+    /// with `HookBuilder::previous_panic_hook`.
     pub fn install(self) {
-        std::panic::set_hook(self.into_panic_hook());
+        let previous = match self.previous_hook {
+            PreviousPanicHook::Replace => None,
+            _ => Some(std::panic::take_hook()),
+        };
+
+        std::panic::set_hook(self.into_chained_panic_hook(previous));
     }
 
     /// Convert self into the type expected by `std::panic::set_hook`.
+    ///
+    /// The returned hook doesn't chain to any previous hook, regardless of
+    /// `HookBuilder::previous_panic_hook`.
     pub fn into_panic_hook(
         self,
+    ) -> Box<dyn Fn(&std::panic::PanicInfo<'_>) + Send + Sync + 'static> {
+        self.into_chained_panic_hook(None)
+    }
+
+    fn into_chained_panic_hook(
+        self,
+        previous: Option<Box<PanicCallback>>,
     ) -> Box<dyn Fn(&std::panic::PanicInfo<'_>) + Send + Sync + 'static> {
         Box::new(move |panic_info| {
-            eprintln!("{}", self.panic_report(panic_info));
+            // Depending on the std version, a panic inside a callback runs the
+            // hook again before aborting. Don't report it a second time.
+            if RUNNING_PANIC_CALLBACKS.with(Cell::get) {
+                return;
+            }
+
+            let panic_number = next_panic_number();
+            run_panic_callbacks(&self.callbacks_before, panic_info);
+
+            let call_previous = |when: &[PreviousPanicHook]| {
+                if let Some(previous) = previous
+                    .as_ref()
+                    .filter(|_| when.contains(&self.previous_hook))
+                {
+                    previous(panic_info);
+                }
+            };
+
+            call_previous(&[
+                PreviousPanicHook::CallBefore,
+                PreviousPanicHook::CallInstead,
+            ]);
+
+            let repeated = self
+                .rate_limit
+                .as_ref()
+                .and_then(|rate_limit| rate_limit.exceeded(panic_info));
+
+            if previous.is_some() && self.previous_hook == PreviousPanicHook::CallInstead {
+                // Still save the crash report, if one is configured
+                if repeated.is_none() {
+                    self.save_crash_report(panic_info);
+                }
+            } else if let Some(count) = repeated {
+                self.write(RepeatedPanic {
+                    panic_info,
+                    count,
+                    hook: &self,
+                });
+            } else {
+                let mut report = self.numbered_panic_report(panic_info, panic_number);
+                report.crash_report = self.save_report(&report);
+                self.write(report);
+            }
+
+            call_previous(&[PreviousPanicHook::CallAfter]);
+
+            run_panic_callbacks(&self.callbacks_after, panic_info);
         })
     }
 
+    /// Print `report` to stderr or the writers added with
+    /// `HookBuilder::panic_writer`
+    fn write(&self, report: impl Display) {
+        if self.writers.is_empty() {
+            eprintln!("{}", report);
+            return;
+        }
+
+        let report = format!("{}\n", report);
+        let mut plain = None;
+        for writer in &self.writers {
+            let report = if writer.colored {
+                &report
+            } else {
+                plain.get_or_insert_with(|| crate::fmt::strip_escape_sequences(&report))
+            };
+
+            (writer.write)(report);
+        }
+    }
+
     /// Construct a panic reporter which prints it's panic report via the
     /// `Display` trait.
     pub fn panic_report<'a>(
         &'a self,
         panic_info: &'a std::panic::PanicInfo<'_>,
+    ) -> PanicReport<'a> {
+        self.numbered_panic_report(panic_info, PANIC_COUNT.load(Ordering::SeqCst))
+    }
+
+    fn numbered_panic_report<'a>(
+        &'a self,
+        panic_info: &'a std::panic::PanicInfo<'_>,
+        panic_number: usize,
     ) -> PanicReport<'a> {
         let v = panic_verbosity();
-        let capture_bt = v != Verbosity::Minimal;
+        let capture_bt = v != Verbosity::Minimal || self.crash_report_dir.is_some();
 
         #[cfg(feature = "capture-spantrace")]
         let span_trace = if self.spantrace_capture_enabled() {
@@ -1016,25 +2347,172 @@ impl PanicHook {
             span_trace,
             backtrace,
             hook: self,
+            crash_report: None,
+            thread: std::thread::current(),
+            panic_number,
         }
     }
+
+    /// Save the full report of a panic to the directory configured with
+    /// `HookBuilder::crash_report_dir`, returning the path of the saved file.
+    ///
+    /// Returns `None` if no crash report directory is configured.
+    pub fn save_crash_report(
+        &self,
+        panic_info: &std::panic::PanicInfo<'_>,
+    ) -> Option<std::io::Result<PathBuf>> {
+        self.crash_report_dir.as_ref()?;
+        let panic_number = PANIC_COUNT.load(Ordering::SeqCst);
+        self.save_report(&self.numbered_panic_report(panic_info, panic_number))
+    }
+
+    fn save_report(&self, report: &PanicReport<'_>) -> Option<std::io::Result<PathBuf>> {
+        let crash_report_dir = self.crash_report_dir.as_ref()?;
+        let text = crate::fmt::strip_escape_sequences(
+            &CrashReport(report, OutputFormat::Text).to_string(),
+        );
+        let json = crash_report_dir
+            .json
+            .then(|| CrashReport(report, OutputFormat::Json).to_string());
+
+        Some(crash_report_dir.save(&text, json.as_deref()))
+    }
+}
+
+/// The number of panics handled by any installed `PanicHook` so far
+static PANIC_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn next_panic_number() -> usize {
+    PANIC_COUNT.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// The quoted name of `thread` or, for unnamed threads, its id
+fn thread_label(thread: &std::thread::Thread) -> String {
+    match thread.name() {
+        Some(name) => format!("'{}'", name),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+/// Limits how often identical panics are reported, configured with
+/// `HookBuilder::panic_rate_limit`
+struct PanicRateLimit {
+    max_reports: usize,
+    period: Duration,
+    /// When the current period of each panic started and how often it
+    /// happened since
+    panics: Mutex<HashMap<PanicKey, (Instant, usize)>>,
+}
+
+/// The message and location of a panic
+type PanicKey = (String, Option<String>);
+
+#[allow(deprecated)]
+impl PanicRateLimit {
+    /// Record a panic and, if it shouldn't be fully reported, return how
+    /// often it happened in the current period
+    fn exceeded(&self, panic_info: &std::panic::PanicInfo<'_>) -> Option<usize> {
+        let key = (
+            panic_payload(panic_info).to_owned(),
+            panic_info.location().map(ToString::to_string),
+        );
+        let now = Instant::now();
+
+        let mut panics = self.panics.lock().unwrap_or_else(PoisonError::into_inner);
+        panics.retain(|_, (start, _)| now.duration_since(*start) < self.period);
+        let (_, count) = panics.entry(key).or_insert((now, 0));
+        *count += 1;
+
+        Some(*count).filter(|&count| count > self.max_reports)
+    }
+}
+
+/// The single line printed instead of the report of a panic exceeding the
+/// `HookBuilder::panic_rate_limit`
+#[allow(deprecated)]
+struct RepeatedPanic<'a> {
+    panic_info: &'a std::panic::PanicInfo<'a>,
+    count: usize,
+    hook: &'a PanicHook,
+}
+
+impl fmt::Display for RepeatedPanic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let theme = self.hook.theme;
+        write!(
+            f,
+            "Suppressed the report of a repeated panic ({} times in {:?}): {}",
+            self.count,
+            self.hook
+                .rate_limit
+                .as_ref()
+                .map_or(Duration::ZERO, |rate_limit| rate_limit.period),
+            panic_payload(self.panic_info).style(theme.panic_message),
+        )?;
+
+        write!(
+            f,
+            " at {}",
+            crate::fmt::LocationSection(
+                self.panic_info.location(),
+                theme,
+                &self.hook.frame_settings
+            )
+        )
+    }
+}
+
+#[allow(deprecated)]
+fn panic_payload<'a>(panic_info: &'a std::panic::PanicInfo<'_>) -> &'a str {
+    let payload = panic_info.payload();
+    payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| payload.downcast_ref::<&str>().cloned())
+        .unwrap_or("<non string panic payload>")
+}
+
+thread_local! {
+    /// Set while the `on_panic_before` or `on_panic_after` callbacks of a
+    /// panic on this thread are running
+    static RUNNING_PANIC_CALLBACKS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `callbacks`, unless callbacks are already running on this thread.
+///
+/// A panic inside a callback aborts the process before the flag is reset, so
+/// there is no need to reset it on unwind.
+#[allow(deprecated)]
+fn run_panic_callbacks(callbacks: &[Box<PanicCallback>], panic_info: &std::panic::PanicInfo<'_>) {
+    if callbacks.is_empty() || RUNNING_PANIC_CALLBACKS.with(|running| running.replace(true)) {
+        return;
+    }
+
+    for callback in callbacks {
+        callback(panic_info);
+    }
+
+    RUNNING_PANIC_CALLBACKS.with(|running| running.set(false));
 }
 
 /// An eyre reporting hook used to construct `EyreHandler`s
 pub struct EyreHook {
     filters: Arc<[Box<FilterCallback>]>,
+    frame_settings: Arc<FrameSettings>,
     #[cfg(feature = "capture-spantrace")]
     capture_span_trace_by_default: bool,
     display_env_section: bool,
     #[cfg(feature = "track-caller")]
     display_location_section: bool,
     theme: Theme,
+    output_format: OutputFormat,
     #[cfg(feature = "issue-url")]
     issue_url: Option<String>,
     #[cfg(feature = "issue-url")]
     issue_metadata: Arc<Vec<(String, Box<dyn Display + Send + Sync + 'static>)>>,
     #[cfg(feature = "issue-url")]
     issue_filter: Arc<IssueFilterCallback>,
+    capture_policy: Option<Box<CapturePolicyCallback>>,
 }
 
 type HookFunc = Box<
@@ -1047,14 +2525,24 @@ type HookFunc = Box<
 impl EyreHook {
     #[allow(unused_variables)]
     pub(crate) fn default(&self, error: &(dyn std::error::Error + 'static)) -> crate::Handler {
-        let backtrace = if lib_verbosity() != Verbosity::Minimal {
-            Some(backtrace::Backtrace::new())
+        let policy = self
+            .capture_policy
+            .as_ref()
+            .map(|policy| policy(error))
+            .unwrap_or(CapturePolicy {
+                backtrace: true,
+                span_trace: true,
+            });
+
+        let backtrace = if policy.backtrace && lib_verbosity() != Verbosity::Minimal {
+            Some(backtrace::Backtrace::new_unresolved())
         } else {
             None
         };
 
         #[cfg(feature = "capture-spantrace")]
-        let span_trace = if self.spantrace_capture_enabled()
+        let span_trace = if policy.span_trace
+            && self.spantrace_capture_enabled()
             && crate::handler::get_deepest_spantrace(error).is_none()
         {
             Some(tracing_error::SpanTrace::capture())
@@ -1064,12 +2552,18 @@ impl EyreHook {
 
         crate::Handler {
             filters: self.filters.clone(),
-            backtrace,
+            frame_settings: self.frame_settings.clone(),
+            unresolved_backtrace: Mutex::new(backtrace),
+            backtrace: OnceCell::new(),
             suppress_backtrace: false,
+            verbosity: None,
             #[cfg(feature = "capture-spantrace")]
             span_trace,
+            #[cfg(feature = "capture-spantrace")]
+            suppress_span_trace: false,
             sections: Vec::new(),
             display_env_section: self.display_env_section,
+            suppress_env_section: false,
             #[cfg(feature = "track-caller")]
             display_location_section: self.display_location_section,
             #[cfg(feature = "issue-url")]
@@ -1079,6 +2573,7 @@ impl EyreHook {
             #[cfg(feature = "issue-url")]
             issue_filter: self.issue_filter.clone(),
             theme: self.theme,
+            output_format: self.output_format,
             #[cfg(feature = "track-caller")]
             location: None,
         }
@@ -1102,41 +2597,127 @@ impl EyreHook {
     }
 }
 
+/// Settings shared by all reports that control how individual frames are
+/// rendered
+pub(crate) struct FrameSettings {
+    pub(crate) source_provider: Box<dyn SourceProvider>,
+    pub(crate) path_remapper: PathRemapper,
+    pub(crate) source_lines_before: u32,
+    pub(crate) source_lines_after: u32,
+    pub(crate) dependency_source_snippets: bool,
+    pub(crate) collapse_repeated_frames: bool,
+    pub(crate) relabel_async_frames: bool,
+    pub(crate) own_crates: Vec<String>,
+    pub(crate) own_paths: Vec<PathBuf>,
+    pub(crate) simplify_symbol_names: bool,
+    pub(crate) display_symbol_hashes: bool,
+    pub(crate) hyperlinks: Hyperlinks,
+}
+
+impl FrameSettings {
+    /// Whether `frame` was explicitly configured to be your own code
+    fn is_own_code(&self, frame: &Frame) -> bool {
+        let own_crate = frame
+            .crate_name()
+            .is_some_and(|name| self.own_crates.iter().any(|own| *own == name));
+
+        let own_path = frame.filename.as_ref().is_some_and(|path| {
+            self.own_paths.iter().any(|root| {
+                path.strip_prefix(root).is_ok_and(|rest| {
+                    let components: Vec<_> = rest.components().map(|c| c.as_os_str()).collect();
+                    let vendored = components.first().is_some_and(|c| *c == "vendor");
+                    let cargo_home = components.windows(2).any(|window| {
+                        window[0] == ".cargo" && (window[1] == "registry" || window[1] == "git")
+                    });
+                    !vendored && !cargo_home
+                })
+            })
+        });
+
+        own_crate || own_path
+    }
+}
+
 pub(crate) struct BacktraceFormatter<'a> {
     pub(crate) filters: &'a [Box<FilterCallback>],
+    pub(crate) frame_settings: &'a FrameSettings,
     pub(crate) inner: &'a backtrace::Backtrace,
     pub(crate) theme: Theme,
+    pub(crate) verbosity: Verbosity,
 }
 
-impl fmt::Display for BacktraceFormatter<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:━^80}", " BACKTRACE ")?;
-
-        // Collect frame info.
-        let frames: Vec<_> = self
+impl BacktraceFormatter<'_> {
+    /// Collect the frames of the backtrace, one per resolved symbol.
+    pub(crate) fn frames(&self) -> Vec<Frame> {
+        let mut frames: Vec<_> = self
             .inner
             .frames()
             .iter()
-            .flat_map(|frame| frame.symbols())
+            .flat_map(|frame| frame.symbols().iter().map(move |sym| (frame, sym)))
             .zip(1usize..)
-            .map(|(sym, n)| Frame {
-                name: sym.name().map(|x| x.to_string()),
-                lineno: sym.lineno(),
-                filename: sym.filename().map(|x| x.into()),
-                n,
+            .map(|((bt_frame, sym), n)| {
+                let mut frame = Frame {
+                    name: sym.name().map(|x| x.to_string()),
+                    lineno: sym.lineno(),
+                    filename: sym
+                        .filename()
+                        .map(|x| self.frame_settings.path_remapper.remap(x).into_owned()),
+                    colno: sym.colno(),
+                    ip: bt_frame.ip() as usize,
+                    module_base: bt_frame.module_base_address().map(|base| base as usize),
+                    n,
+                    own_code: false,
+                    dependency_code: false,
+                    label: None,
+                };
+
+                frame.own_code = self.frame_settings.is_own_code(&frame);
+                frame.dependency_code = !frame.own_code && frame.looks_like_dependency_code();
+                frame
             })
             .collect();
 
-        let mut filtered_frames = frames.iter().collect();
-        match env::var("COLORBT_SHOW_HIDDEN").ok().as_deref() {
-            Some("1") | Some("on") | Some("y") => (),
-            _ => {
-                for filter in self.filters {
-                    filter(&mut filtered_frames);
-                }
+        if self.frame_settings.relabel_async_frames {
+            relabel_async_frames(&mut frames);
+        }
+
+        frames
+    }
+
+    /// Apply the configured filters and the rules of `COLORBT_FILTER` to
+    /// `frames`, unless `COLORBT_SHOW_HIDDEN` is set, and return the remaining
+    /// frames in their original order.
+    pub(crate) fn filter<'b>(&self, frames: &'b [Frame]) -> Vec<&'b Frame> {
+        let mut filtered_frames: Vec<&Frame> = frames.iter().collect();
+        if !show_hidden() {
+            for filter in self.filters {
+                filter(&mut filtered_frames);
+            }
+
+            let rules = EnvRules::from_env();
+            if !rules.is_empty() {
+                let visible: HashSet<usize> = filtered_frames.iter().map(|frame| frame.n).collect();
+                filtered_frames = frames
+                    .iter()
+                    .filter(|frame| rules.is_visible(frame, visible.contains(&frame.n)))
+                    .collect();
             }
         }
 
+        // Don't let filters mess with the order.
+        filtered_frames.sort_by_key(|x| x.n);
+        filtered_frames
+    }
+}
+
+impl fmt::Display for BacktraceFormatter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:━^80}", " BACKTRACE ")?;
+
+        // Collect frame info.
+        let frames = self.frames();
+        let filtered_frames = self.filter(&frames);
+
         if filtered_frames.is_empty() {
             // TODO: Would probably look better centered.
             return write!(f, "\n<empty backtrace>");
@@ -1144,23 +2725,12 @@ impl fmt::Display for BacktraceFormatter<'_> {
 
         let mut separated = f.header("\n");
 
-        // Don't let filters mess with the order.
-        filtered_frames.sort_by_key(|x| x.n);
-
         let mut buf = String::new();
 
-        macro_rules! print_hidden {
-            ($n:expr) => {
-                let n = $n;
+        macro_rules! print_centered {
+            ($($arg:tt)*) => {
                 buf.clear();
-                write!(
-                    &mut buf,
-                    "{decorator} {n} frame{plural} hidden {decorator}",
-                    n = n,
-                    plural = if n == 1 { "" } else { "s" },
-                    decorator = "⋮",
-                )
-                .expect("writing to strings doesn't panic");
+                write!(&mut buf, $($arg)*).expect("writing to strings doesn't panic");
                 write!(
                     &mut separated.ready(),
                     "{:^80}",
@@ -1169,34 +2739,299 @@ impl fmt::Display for BacktraceFormatter<'_> {
             };
         }
 
+        macro_rules! print_hidden {
+            ($hidden:expr) => {
+                let hidden: &[Frame] = $hidden;
+                let n = hidden.len();
+                print_centered!(
+                    "{decorator} {n} frame{plural} hidden{crates} {decorator}",
+                    n = n,
+                    plural = if n == 1 { "" } else { "s" },
+                    crates = HiddenCrates(hidden),
+                    decorator = "⋮",
+                );
+            };
+        }
+
+        let rows = if self.frame_settings.collapse_repeated_frames && !show_hidden() {
+            collapse_repeated_frames(&filtered_frames)
+        } else {
+            filtered_frames
+                .iter()
+                .map(|frame| Row::Frame(frame))
+                .collect()
+        };
+
         let mut last_n = 0;
-        for frame in &filtered_frames {
-            let frame_delta = frame.n - last_n - 1;
-            if frame_delta != 0 {
-                print_hidden!(frame_delta);
+        for row in rows {
+            match row {
+                Row::Frame(frame) => {
+                    let frame_delta = frame.n - last_n - 1;
+                    if frame_delta != 0 {
+                        // Frames are numbered starting at 1
+                        print_hidden!(&frames[last_n..frame.n - 1]);
+                    }
+                    write!(
+                        &mut separated.ready(),
+                        "{}",
+                        StyledFrame(frame, self.theme, self.frame_settings, self.verbosity)
+                    )?;
+                    last_n = frame.n;
+                }
+                Row::Repeated { first, last, cycle } => {
+                    print_centered!(
+                        "{decorator} frames {first}–{last} repeat{cycle} {decorator}",
+                        first = first,
+                        last = last,
+                        cycle = if cycle == 1 {
+                            String::new()
+                        } else {
+                            format!(" (cycle of {})", cycle)
+                        },
+                        decorator = "⋮",
+                    );
+                    last_n = last;
+                }
             }
-            write!(&mut separated.ready(), "{}", StyledFrame(frame, self.theme))?;
-            last_n = frame.n;
         }
 
         let last_filtered_n = filtered_frames.last().unwrap().n;
         let last_unfiltered_n = frames.last().unwrap().n;
         if last_filtered_n < last_unfiltered_n {
-            print_hidden!(last_unfiltered_n - last_filtered_n);
+            print_hidden!(&frames[last_filtered_n..]);
         }
 
         Ok(())
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-pub(crate) enum Verbosity {
+/// Label the `{{closure}}` frames of async fn bodies with their async fn.
+///
+/// Only the displayed label changes, filters and JSON reports still see the
+/// original symbol name.
+///
+/// A closure is considered to be the body of an async fn if it is polled by an
+/// executor or adapter frame, or awaited by another async fn body. Closures
+/// nested in the body of their caller, e.g. `foo::{{closure}}::{{closure}}`
+/// called by `foo::{{closure}}`, are left alone, as they are usually regular
+/// closures.
+fn relabel_async_frames(frames: &mut [Frame]) {
+    // Callers come after the frames they call
+    let mut caller: Option<(String, bool)> = None;
+
+    for frame in frames.iter_mut().rev() {
+        let name = match frame.name.as_deref() {
+            Some(name) => name,
+            None => {
+                caller = None;
+                continue;
+            }
+        };
+
+        let (path, hash_suffix) = split_hash_suffix(name);
+        let original_path = path.to_owned();
+        let polled = frame_is_polled(&caller, path);
+        let is_async_body = polled
+            && ["::{{closure}}", "::{closure#0}"]
+                .iter()
+                .any(|suffix| path.ends_with(suffix));
+
+        if is_async_body {
+            let end = path.rfind("::").expect("suffix contains a path separator");
+            frame.label = Some(format!("{}{}", &path[..end], hash_suffix.unwrap_or("")));
+        }
+
+        let is_executor_code = frame.is_async_executor_code();
+        caller = Some((original_path, is_async_body || is_executor_code));
+    }
+}
+
+fn frame_is_polled(caller: &Option<(String, bool)>, path: &str) -> bool {
+    match caller {
+        Some((caller_path, true)) => !path
+            .strip_prefix(caller_path.as_str())
+            .is_some_and(|rest| rest.starts_with("::")),
+        _ => false,
+    }
+}
+
+/// Summarizes which crates a run of hidden frames belongs to, e.g.
+/// ` (tokio ×22, hyper ×9)`
+struct HiddenCrates<'a>(&'a [Frame]);
+
+impl fmt::Display for HiddenCrates<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Crates beyond this are only hinted at
+        const MAX_CRATES: usize = 4;
+
+        let mut crates: Vec<(Cow<'_, str>, usize)> = vec![];
+        for name in self.0.iter().filter_map(Frame::crate_name) {
+            match crates.iter_mut().find(|(krate, _)| *krate == name) {
+                Some((_, count)) => *count += 1,
+                None => crates.push((name, 1)),
+            }
+        }
+
+        if crates.is_empty() {
+            return Ok(());
+        }
+
+        // Stable, so crates with equal counts stay in order of appearance
+        crates.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        f.write_str(" (")?;
+        for (i, (krate, count)) in crates.iter().take(MAX_CRATES).enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} ×{}", krate, count)?;
+        }
+        if crates.len() > MAX_CRATES {
+            f.write_str(", …")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// An entry of a formatted backtrace
+enum Row<'a> {
+    Frame(&'a Frame),
+    /// The frames `first..=last` repeat the `cycle` frames right before them
+    Repeated {
+        first: usize,
+        last: usize,
+        cycle: usize,
+    },
+}
+
+/// The longest sequence of frames that is detected as a repeating cycle
+const MAX_CYCLE_LEN: usize = 8;
+
+/// How often a cycle has to occur in a row before its repetitions are collapsed
+const MIN_CYCLE_REPEATS: usize = 3;
+
+/// Collapse consecutive repetitions of the same frame, or of the same cycle of
+/// frames, as produced by deep recursion.
+///
+/// Only runs of adjacent frames are collapsed, so a repetition never spans
+/// frames that were removed by filters. Unresolved frames are never considered
+/// repetitions, as their addresses are all that is left to identify them.
+fn collapse_repeated_frames<'a>(frames: &[&'a Frame]) -> Vec<Row<'a>> {
+    let same_frame = |a: &Frame, b: &Frame| {
+        a.name.is_some()
+            && a.name == b.name
+            && a.filename == b.filename
+            && a.lineno == b.lineno
+            && a.colno == b.colno
+    };
+
+    let mut rows = Vec::with_capacity(frames.len());
+    let mut i = 0;
+    while i < frames.len() {
+        let adjacent = 1 + frames[i..]
+            .windows(2)
+            .take_while(|pair| pair[1].n == pair[0].n + 1)
+            .count();
+        let run = &frames[i..i + adjacent];
+
+        let repeating_cycle = (1..=MAX_CYCLE_LEN).find_map(|cycle| {
+            let candidate = run.get(..cycle)?;
+            let repeats = run
+                .chunks_exact(cycle)
+                .take_while(|chunk| chunk.iter().zip(candidate).all(|(a, b)| same_frame(a, b)))
+                .count();
+
+            Some((cycle, repeats)).filter(|_| repeats >= MIN_CYCLE_REPEATS)
+        });
+
+        match repeating_cycle {
+            Some((cycle, repeats)) => {
+                rows.extend(frames[i..i + cycle].iter().map(|frame| Row::Frame(frame)));
+                rows.push(Row::Repeated {
+                    first: frames[i + cycle].n,
+                    last: frames[i + cycle * repeats - 1].n,
+                    cycle,
+                });
+                i += cycle * repeats;
+            }
+            None => {
+                rows.push(Row::Frame(frames[i]));
+                i += 1;
+            }
+        }
+    }
+
+    rows
+}
+
+fn show_hidden() -> bool {
+    matches!(
+        env::var("COLORBT_SHOW_HIDDEN").ok().as_deref(),
+        Some("1") | Some("on") | Some("y")
+    )
+}
+
+/// The level of detail of error and panic reports
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Verbosity {
+    /// Don't capture backtraces
     Minimal,
+    /// Capture backtraces and display them without source snippets, like
+    /// `RUST_BACKTRACE=1`
     Medium,
+    /// Capture backtraces and display them with source snippets and frame
+    /// addresses, like `RUST_BACKTRACE=full`
     Full,
 }
 
+/// 0 if unset, otherwise the `Verbosity` shifted by one
+static VERBOSITY_OVERRIDE: AtomicU8 = AtomicU8::new(0);
+
+/// Override the verbosity of error and panic reports at runtime
+///
+/// # Details
+///
+/// The override takes precedence over the `RUST_BACKTRACE` and
+/// `RUST_LIB_BACKTRACE` environment variables, which are used again after
+/// passing `None`. It applies to all reports created afterwards and also
+/// controls whether backtraces are captured. While it is set, reports don't
+/// suggest setting `RUST_BACKTRACE`.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::config::{set_verbosity, Verbosity};
+///
+/// let verbose = std::env::args().any(|arg| arg == "--verbose");
+/// if verbose {
+///     set_verbosity(Some(Verbosity::Full));
+/// }
+/// ```
+pub fn set_verbosity(verbosity: Option<Verbosity>) {
+    let value = match verbosity {
+        None => 0,
+        Some(Verbosity::Minimal) => 1,
+        Some(Verbosity::Medium) => 2,
+        Some(Verbosity::Full) => 3,
+    };
+
+    VERBOSITY_OVERRIDE.store(value, Ordering::Relaxed);
+}
+
+pub(crate) fn verbosity_override() -> Option<Verbosity> {
+    match VERBOSITY_OVERRIDE.load(Ordering::Relaxed) {
+        1 => Some(Verbosity::Minimal),
+        2 => Some(Verbosity::Medium),
+        3 => Some(Verbosity::Full),
+        _ => None,
+    }
+}
+
 pub(crate) fn panic_verbosity() -> Verbosity {
+    if let Some(verbosity) = verbosity_override() {
+        return verbosity;
+    }
+
     match env::var("RUST_BACKTRACE") {
         Ok(s) if s == "full" => Verbosity::Full,
         Ok(s) if s != "0" => Verbosity::Medium,
@@ -1205,6 +3040,10 @@ pub(crate) fn panic_verbosity() -> Verbosity {
 }
 
 pub(crate) fn lib_verbosity() -> Verbosity {
+    if let Some(verbosity) = verbosity_override() {
+        return verbosity;
+    }
+
     match env::var("RUST_LIB_BACKTRACE").or_else(|_| env::var("RUST_BACKTRACE")) {
         Ok(s) if s == "full" => Verbosity::Full,
         Ok(s) if s != "0" => Verbosity::Medium,
@@ -1212,6 +3051,210 @@ pub(crate) fn lib_verbosity() -> Verbosity {
     }
 }
 
+/// Controls whether reports are colored
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ColorMode {
+    /// Colorize output if stderr is a terminal, honoring the `NO_COLOR` and
+    /// `CLICOLOR_FORCE` environment variables
+    Auto,
+    /// Always colorize output. This is the default
+    Always,
+    /// Never colorize output
+    Never,
+}
+
+impl ColorMode {
+    pub(crate) fn colors_enabled(self) -> bool {
+        use std::io::IsTerminal;
+
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                let no_color = env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty());
+                let force_color =
+                    env::var_os("CLICOLOR_FORCE").is_some_and(|val| !val.is_empty() && val != "0");
+
+                if no_color {
+                    false
+                } else if force_color {
+                    true
+                } else {
+                    std::io::stderr().is_terminal()
+                }
+            }
+        }
+    }
+}
+
+/// A destination for panic reports, added with `HookBuilder::panic_writer`
+pub struct PanicWriter {
+    write: Box<dyn Fn(&str) + Send + Sync + 'static>,
+    colored: bool,
+}
+
+impl PanicWriter {
+    /// Write panic reports to the writer returned by `make_writer`, which is
+    /// called once per panic. Errors while writing are ignored.
+    pub fn new<F, W>(make_writer: F) -> Self
+    where
+        F: Fn() -> W + Send + Sync + 'static,
+        W: std::io::Write,
+    {
+        PanicWriter::try_new(move || Ok(make_writer()))
+    }
+
+    /// Write panic reports to the writer returned by `make_writer`, which is
+    /// called once per panic and may fail, e.g. if a log file can't be
+    /// opened. In that case the report isn't written to this destination.
+    pub fn try_new<F, W>(make_writer: F) -> Self
+    where
+        F: Fn() -> std::io::Result<W> + Send + Sync + 'static,
+        W: std::io::Write,
+    {
+        PanicWriter::callback(move |report| {
+            if let Ok(mut writer) = make_writer() {
+                let _ = writer.write_all(report.as_bytes());
+                let _ = writer.flush();
+            }
+        })
+    }
+
+    /// Pass rendered panic reports to `callback`
+    pub fn callback<F>(callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        PanicWriter {
+            write: Box::new(callback),
+            colored: true,
+        }
+    }
+
+    /// Configures whether the report keeps its colors, or is stripped of all
+    /// escape sequences, e.g. for writing it to a file.
+    ///
+    /// Defaults to `true`. Reports are never colored if colors are disabled
+    /// via `HookBuilder::color_mode`.
+    pub fn colored(mut self, cond: bool) -> Self {
+        self.colored = cond;
+        self
+    }
+}
+
+impl fmt::Debug for PanicWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicWriter")
+            .field("colored", &self.colored)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The target of the hyperlinks enabled with `HookBuilder::hyperlinks`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Hyperlinks {
+    /// Don't emit hyperlinks. This is the default
+    Disabled,
+    /// Link to the `file://` URI of the source file
+    File,
+    /// Link to a URL built from a template, e.g.
+    /// `vscode://file/{path}:{line}:{col}`. `{path}` is replaced with the
+    /// percent-encoded absolute path of the source file, `{line}` and `{col}`
+    /// with the location in it, or `1` if unknown
+    Editor(String),
+}
+
+impl Hyperlinks {
+    /// The link target for a source location, if hyperlinks are enabled
+    pub(crate) fn url(&self, path: &Path, line: Option<u32>, col: Option<u32>) -> Option<String> {
+        if *self == Hyperlinks::Disabled {
+            return None;
+        }
+
+        // Relative paths, e.g. from `#[track_caller]` locations, are relative
+        // to the workspace the binary was built in, which is usually the
+        // current directory during development.
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            env::current_dir().ok()?.join(path)
+        };
+        let path = path.to_string_lossy();
+
+        match self {
+            Hyperlinks::Disabled => None,
+            Hyperlinks::File => {
+                let path = percent_encode_path(&path);
+                if path.starts_with('/') {
+                    Some(format!("file://{}", path))
+                } else {
+                    Some(format!("file:///{}", path))
+                }
+            }
+            Hyperlinks::Editor(template) => Some(
+                template
+                    .replace("{path}", &percent_encode_path(&path))
+                    .replace("{line}", &line.unwrap_or(1).to_string())
+                    .replace("{col}", &col.unwrap_or(1).to_string()),
+            ),
+        }
+    }
+}
+
+/// Percent-encode `path` for use in a URL, with `/` as the separator
+fn percent_encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => write!(encoded, "%{:02X}", byte).expect("writing to strings doesn't panic"),
+        }
+    }
+
+    encoded
+}
+
+/// The format used to render error and panic reports
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// Colorful, human readable text. This is the default
+    Text,
+    /// A single machine readable JSON document per report
+    Json,
+}
+
+impl OutputFormat {
+    /// Apply the `COLORBT_FORMAT` environment variable override, if any
+    pub(crate) fn resolve(self) -> Self {
+        match env::var("COLORBT_FORMAT").ok().as_deref() {
+            Some("json") => OutputFormat::Json,
+            Some("text") => OutputFormat::Text,
+            _ => self,
+        }
+    }
+}
+
+/// What `PanicHook::install` does with the previously installed panic hook,
+/// configured with `HookBuilder::previous_panic_hook`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PreviousPanicHook {
+    /// Replace the previous hook, which is never called. This is the default
+    Replace,
+    /// Call the previous hook before printing the report
+    CallBefore,
+    /// Call the previous hook after printing the report
+    CallAfter,
+    /// Call the previous hook instead of printing the report, e.g. if it
+    /// already reports panics. Crash reports are still saved
+    CallInstead,
+}
+
 /// Callback for filtering a vector of `Frame`s
 pub type FilterCallback = dyn Fn(&mut Vec<&Frame>) + Send + Sync + 'static;
 
@@ -1219,3 +3262,82 @@ pub type FilterCallback = dyn Fn(&mut Vec<&Frame>) + Send + Sync + 'static;
 #[cfg(feature = "issue-url")]
 #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
 pub type IssueFilterCallback = dyn Fn(crate::ErrorKind<'_>) -> bool + Send + Sync + 'static;
+
+/// Callback run before or after a panic report is printed
+#[allow(deprecated)]
+pub type PanicCallback = dyn Fn(&std::panic::PanicInfo<'_>) + Send + Sync + 'static;
+
+/// Callback for deciding what to capture when an error report is created
+pub type CapturePolicyCallback =
+    dyn Fn(&(dyn std::error::Error + 'static)) -> CapturePolicy + Send + Sync + 'static;
+
+/// What to capture for an error report, as returned by the callback passed to
+/// `HookBuilder::capture_policy`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CapturePolicy {
+    /// Capture a `Backtrace`, if the verbosity isn't `Verbosity::Minimal`
+    pub backtrace: bool,
+    /// Capture a `SpanTrace`, if span trace capture is enabled
+    pub span_trace: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(name: &str, filename: Option<&str>) -> Frame {
+        Frame {
+            n: 0,
+            name: Some(name.to_owned()),
+            lineno: None,
+            filename: filename.map(PathBuf::from),
+            colno: None,
+            ip: 0,
+            module_base: None,
+            own_code: false,
+            dependency_code: false,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn crate_names() {
+        let crate_name = |name, filename| frame(name, filename).crate_name().map(Cow::into_owned);
+
+        assert_eq!(
+            crate_name("color_eyre::config::install", None).as_deref(),
+            Some("color_eyre")
+        );
+        assert_eq!(
+            crate_name(
+                "<tokio::runtime::task::Task as core::future::future::Future>::poll",
+                None
+            )
+            .as_deref(),
+            Some("tokio")
+        );
+        assert_eq!(
+            crate_name(
+                "hyper::client::connect",
+                Some("/home/me/.cargo/registry/src/index.crates.io-0/tokio-util-0.7.8/src/lib.rs")
+            )
+            .as_deref(),
+            Some("tokio_util")
+        );
+        assert_eq!(crate_name("__rust_try", None), None);
+    }
+
+    #[test]
+    fn crate_names_of_v0_symbols() {
+        let crate_name = |name| frame(name, None).crate_name().map(Cow::into_owned);
+
+        assert_eq!(
+            crate_name("color_eyre[495282a0244a7363]::config::install").as_deref(),
+            Some("color_eyre")
+        );
+        assert_eq!(
+            crate_name("<alloc[fdfd2bd8633a6659]::boxed::Box<F> as core[c1f1a4ba060b9bfa]::ops::function::Fn<()>>::call").as_deref(),
+            Some("alloc")
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Crash report files written by the panic hook
+use std::{
+    collections::BTreeSet,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const PREFIX: &str = "panic-";
+const EXTENSIONS: &[&str] = &["txt", "json"];
+
+/// The crash report directory configured with `HookBuilder::crash_report_dir`
+#[derive(Debug)]
+pub(crate) struct CrashReportDir {
+    pub(crate) dir: PathBuf,
+    pub(crate) retention: usize,
+    pub(crate) json: bool,
+}
+
+impl CrashReportDir {
+    /// Save a report as `panic-<timestamp>-<pid>.txt`, plus a `.json` file
+    /// with the same name if `json` is given, then delete the oldest reports
+    /// exceeding the retention limit. Returns the path of the text report.
+    pub(crate) fn save(&self, text: &str, json: Option<&str>) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let base = format!("{}{}-{}", PREFIX, timestamp(now), std::process::id());
+        let (stem, path) = (0..)
+            .map(|n| match n {
+                0 => base.clone(),
+                n => format!("{}-{}", base, n),
+            })
+            .map(|stem| {
+                let path = self.dir.join(format!("{}.txt", stem));
+                (stem, path)
+            })
+            .find(|(_, path)| !path.exists())
+            .expect("some report name is free");
+
+        write_new(&path, text)?;
+        if let Some(json) = json {
+            write_new(&self.dir.join(format!("{}.json", stem)), json)?;
+        }
+
+        self.remove_old_reports(&stem);
+
+        Ok(path)
+    }
+
+    /// Delete the oldest reports such that at most `retention` reports,
+    /// including the one named `current`, are left. Errors are ignored.
+    fn remove_old_reports(&self, current: &str) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        let stems: BTreeSet<String> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|name| {
+                let (stem, extension) = name.rsplit_once('.')?;
+                Some(stem.to_owned())
+                    .filter(|stem| stem.starts_with(PREFIX) && stem != current)
+                    .filter(|_| EXTENSIONS.contains(&extension))
+            })
+            .collect();
+
+        let excess = (stems.len() + 1).saturating_sub(self.retention.max(1));
+        for stem in stems.iter().take(excess) {
+            for extension in EXTENSIONS {
+                let _ = fs::remove_file(self.dir.join(format!("{}.{}", stem, extension)));
+            }
+        }
+    }
+}
+
+fn write_new(path: &Path, contents: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.flush()
+}
+
+/// The UTC time `since_epoch` after the unix epoch as
+/// `2022-07-11T14-03-59.123Z`, which sorts chronologically and is a valid file
+/// name on all platforms
+fn timestamp(since_epoch: Duration) -> String {
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs = secs % 86400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}-{:02}-{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Convert days since the unix epoch to a proleptic Gregorian calendar date,
+/// see <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(timestamp(Duration::ZERO), "1970-01-01T00-00-00.000Z");
+        assert_eq!(
+            timestamp(Duration::from_millis(1_657_548_239_123)),
+            "2022-07-11T14-03-59.123Z"
+        );
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(47_541), (2100, 3, 1));
+    }
+}
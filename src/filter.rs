@@ -0,0 +1,357 @@
+//! Declarative frame filters and the rules of the `COLORBT_FILTER` environment
+//! variable
+use crate::config::{FilterCallback, Frame};
+use std::{env, path::PathBuf};
+
+/// Selects the backtrace frames a `FrameFilter` step applies to
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::config::FrameMatcher;
+///
+/// let tokio = FrameMatcher::prefix("tokio::");
+/// let tokio_runtime = FrameMatcher::symbol_glob("tokio::runtime::*::poll*");
+/// let vendored = FrameMatcher::glob("*/vendor/*");
+/// let hyper = FrameMatcher::crate_name("hyper");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameMatcher(Matcher);
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Prefix(String),
+    SymbolGlob(String),
+    Glob(String),
+    Path(PathBuf),
+    Crate(String),
+}
+
+impl FrameMatcher {
+    /// Match frames whose symbol name starts with `prefix`.
+    ///
+    /// The leading `<` of trait impl symbols such as
+    /// `<tokio::runtime::task::Task as Future>::poll` is ignored.
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        FrameMatcher(Matcher::Prefix(prefix.into()))
+    }
+
+    /// Match frames whose symbol name matches `glob`.
+    ///
+    /// `*` matches any sequence of characters and `?` matches any single
+    /// character. The glob has to match the whole name, so it usually ends
+    /// with `*`. Like with `prefix`, a leading `<` of the name is ignored.
+    pub fn symbol_glob(glob: impl Into<String>) -> Self {
+        FrameMatcher(Matcher::SymbolGlob(glob.into()))
+    }
+
+    /// Match frames whose file path matches `glob`.
+    ///
+    /// `*` matches any sequence of characters, including path separators, and
+    /// `?` matches any single character. The glob has to match the whole
+    /// path, after any `HookBuilder::remap_path_prefix` rules were applied.
+    pub fn glob(glob: impl Into<String>) -> Self {
+        FrameMatcher(Matcher::Glob(glob.into()))
+    }
+
+    /// Match frames whose file path is `path` or lies inside of it
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        FrameMatcher(Matcher::Path(path.into()))
+    }
+
+    /// Match frames of the crate called `name`.
+    ///
+    /// `name` is the crate name as used in paths, e.g. `tokio_util` for the
+    /// package `tokio-util`. The crate of a frame is determined from its path
+    /// in the cargo registry or, failing that, from the first segment of its
+    /// symbol name.
+    pub fn crate_name(name: impl Into<String>) -> Self {
+        FrameMatcher(Matcher::Crate(name.into()))
+    }
+
+    /// Check whether `frame` is matched
+    pub fn matches(&self, frame: &Frame) -> bool {
+        match &self.0 {
+            Matcher::Prefix(prefix) => frame
+                .name
+                .as_deref()
+                .is_some_and(|name| name.trim_start_matches('<').starts_with(prefix.as_str())),
+            Matcher::SymbolGlob(glob) => frame
+                .name
+                .as_deref()
+                .is_some_and(|name| glob_match(glob, name.trim_start_matches('<'))),
+            Matcher::Glob(glob) => frame
+                .filename
+                .as_deref()
+                .is_some_and(|path| glob_match(glob, &path.to_string_lossy())),
+            Matcher::Path(prefix) => frame
+                .filename
+                .as_deref()
+                .is_some_and(|path| path.starts_with(prefix)),
+            Matcher::Crate(name) => frame.crate_name().as_deref() == Some(name.as_str()),
+        }
+    }
+}
+
+/// Builder for frame filters that hide or show frames selected by
+/// `FrameMatcher`s
+///
+/// # Details
+///
+/// The steps of a `FrameFilter` are applied in the order they were added, so
+/// a later `show` can reveal frames hidden by an earlier `hide`. Like any
+/// other filter, a `FrameFilter` only sees the frames left over by the filters
+/// registered before it and can't reveal frames those have removed.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::config::{FrameFilter, FrameMatcher, HookBuilder};
+///
+/// let filter = FrameFilter::new()
+///     .hide(FrameMatcher::crate_name("tokio"))
+///     .hide(FrameMatcher::prefix("hyper::"))
+///     .show(FrameMatcher::prefix("hyper::client::"))
+///     .drop_below(FrameMatcher::prefix("my_app::main"));
+///
+/// HookBuilder::default()
+///     .add_frame_filter(filter.into())
+///     .install()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameFilter {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Hide(FrameMatcher),
+    Show(FrameMatcher),
+    DropAbove(FrameMatcher),
+    DropBelow(FrameMatcher),
+}
+
+impl FrameFilter {
+    /// Construct a `FrameFilter` that doesn't filter anything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hide the frames matched by `matcher`
+    pub fn hide(mut self, matcher: FrameMatcher) -> Self {
+        self.steps.push(Step::Hide(matcher));
+        self
+    }
+
+    /// Show the frames matched by `matcher`, even if an earlier step of this
+    /// filter hid them
+    pub fn show(mut self, matcher: FrameMatcher) -> Self {
+        self.steps.push(Step::Show(matcher));
+        self
+    }
+
+    /// Hide every frame above the first frame matched by `matcher`, which
+    /// itself stays visible. Nothing is hidden if no frame matches.
+    pub fn drop_above(mut self, matcher: FrameMatcher) -> Self {
+        self.steps.push(Step::DropAbove(matcher));
+        self
+    }
+
+    /// Hide every frame below the last frame matched by `matcher`, which
+    /// itself stays visible. Nothing is hidden if no frame matches.
+    pub fn drop_below(mut self, matcher: FrameMatcher) -> Self {
+        self.steps.push(Step::DropBelow(matcher));
+        self
+    }
+
+    /// Append the steps of `other` to this filter
+    pub fn chain(mut self, other: FrameFilter) -> Self {
+        self.steps.extend(other.steps);
+        self
+    }
+
+    /// Remove the frames hidden by this filter from `frames`
+    pub fn apply(&self, frames: &mut Vec<&Frame>) {
+        let mut visible = vec![true; frames.len()];
+
+        for step in &self.steps {
+            match step {
+                Step::Hide(matcher) | Step::Show(matcher) => {
+                    let show = matches!(step, Step::Show(_));
+                    for (visible, frame) in visible.iter_mut().zip(frames.iter()) {
+                        if matcher.matches(frame) {
+                            *visible = show;
+                        }
+                    }
+                }
+                Step::DropAbove(matcher) => {
+                    if let Some(first) = frames.iter().position(|frame| matcher.matches(frame)) {
+                        visible[..first].fill(false);
+                    }
+                }
+                Step::DropBelow(matcher) => {
+                    if let Some(last) = frames.iter().rposition(|frame| matcher.matches(frame)) {
+                        visible[last + 1..].fill(false);
+                    }
+                }
+            }
+        }
+
+        let mut visible = visible.into_iter();
+        frames.retain(|_| visible.next().unwrap_or(true));
+    }
+}
+
+impl From<FrameFilter> for Box<FilterCallback> {
+    fn from(filter: FrameFilter) -> Self {
+        Box::new(move |frames| filter.apply(frames))
+    }
+}
+
+/// A single `+pattern` or `-pattern` entry of `COLORBT_FILTER`
+struct Rule {
+    show: bool,
+    matcher: FrameMatcher,
+}
+
+/// The rules of `COLORBT_FILTER`, applied after the filters registered on the
+/// `HookBuilder`
+pub(crate) struct EnvRules(Vec<Rule>);
+
+impl EnvRules {
+    /// Parse the comma separated rules of `COLORBT_FILTER`.
+    ///
+    /// Entries starting with `-` hide matching frames, entries starting with
+    /// `+` show them even if they were hidden by a filter. Patterns containing
+    /// a path separator are globs matched against the file path of a frame,
+    /// other patterns containing `*` or `?` are globs matched against its
+    /// symbol name and all others are prefixes of its symbol name. Entries
+    /// without a sign are ignored.
+    pub(crate) fn from_env() -> Self {
+        let rules = env::var("COLORBT_FILTER").unwrap_or_default();
+
+        EnvRules(
+            rules
+                .split(',')
+                .map(str::trim)
+                .filter_map(|rule| {
+                    let show = match rule.chars().next()? {
+                        '+' => true,
+                        '-' => false,
+                        _ => return None,
+                    };
+
+                    Some(&rule[1..])
+                        .filter(|pattern| !pattern.is_empty())
+                        .map(|pattern| Rule {
+                            show,
+                            matcher: parse_pattern(pattern),
+                        })
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Decide whether `frame` is displayed, given whether it survived the
+    /// `HookBuilder` filters. Later rules take precedence over earlier ones.
+    pub(crate) fn is_visible(&self, frame: &Frame, visible: bool) -> bool {
+        self.0
+            .iter()
+            .rev()
+            .find(|rule| rule.matcher.matches(frame))
+            .map_or(visible, |rule| rule.show)
+    }
+}
+
+/// Patterns containing a path separator match the file path, all others the
+/// symbol name
+fn parse_pattern(pattern: &str) -> FrameMatcher {
+    if pattern.contains(['/', '\\']) {
+        FrameMatcher::glob(pattern)
+    } else if pattern.contains(['*', '?']) {
+        FrameMatcher::symbol_glob(pattern)
+    } else {
+        FrameMatcher::prefix(pattern)
+    }
+}
+
+/// Match `text` against a glob where `*` matches any sequence of characters,
+/// including path separators, and `?` matches a single character
+pub(crate) fn glob_match(glob: &str, text: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut g, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently matches up to
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, t));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    g = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("tokio::*", "tokio::runtime::task::poll"));
+        assert!(glob_match("*::poll", "tokio::runtime::task::poll"));
+        assert!(glob_match("*/vendor/*", "/app/vendor/hyper/src/lib.rs"));
+        assert!(glob_match(
+            "tokio::runtime::*::poll*",
+            "tokio::runtime::task::poll_inner"
+        ));
+        assert!(glob_match("h?per", "hyper"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a**b", "ab"));
+
+        assert!(!glob_match("tokio::*", "hyper::client"));
+        assert!(!glob_match("h?per", "hper"));
+        assert!(!glob_match("*::poll", "tokio::runtime::task::poll_inner"));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn globs_backtrack() {
+        assert!(glob_match("*ab*ab", "aab-abab"));
+        assert!(glob_match("*a*b*c", "xaxbxbxc"));
+        assert!(!glob_match("*ab*ab", "aab-aba"));
+    }
+
+    #[test]
+    fn env_patterns() {
+        assert!(matches!(parse_pattern("tokio::").0, Matcher::Prefix(_)));
+        assert!(matches!(
+            parse_pattern("tokio::*::poll").0,
+            Matcher::SymbolGlob(_)
+        ));
+        assert!(matches!(parse_pattern("*/vendor/*").0, Matcher::Glob(_)));
+        assert!(matches!(parse_pattern(r"C:\vendor\*").0, Matcher::Glob(_)));
+    }
+}
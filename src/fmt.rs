@@ -1,21 +1,39 @@
 //! Module for new types that isolate complext formatting
-use std::fmt;
+use std::{fmt, path::Path};
 
 use owo_colors::OwoColorize;
 
 pub(crate) struct LocationSection<'a>(
     pub(crate) Option<&'a std::panic::Location<'a>>,
     pub(crate) crate::config::Theme,
+    pub(crate) &'a crate::config::FrameSettings,
 );
 
 impl fmt::Display for LocationSection<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let theme = self.1;
+        let paths = &self.2.path_remapper;
         // If known, print panic location.
         if let Some(loc) = self.0 {
-            write!(f, "{}", loc.file().style(theme.panic_file))?;
-            write!(f, ":")?;
-            write!(f, "{}", loc.line().style(theme.panic_line_number))?;
+            let path = paths.remap(Path::new(loc.file()));
+            let file = paths.shorten(&path);
+            let url = self
+                .2
+                .hyperlinks
+                .url(&path, Some(loc.line()), Some(loc.column()));
+            write!(
+                f,
+                "{}",
+                Hyperlink(
+                    url.as_deref(),
+                    format_args!(
+                        "{}:{}:{}",
+                        file.style(theme.panic_file),
+                        loc.line().style(theme.panic_line_number),
+                        loc.column().style(theme.panic_line_number)
+                    )
+                )
+            )?;
         } else {
             write!(f, "<unknown>")?;
         }
@@ -23,3 +41,57 @@ impl fmt::Display for LocationSection<'_> {
         Ok(())
     }
 }
+
+/// Wraps the inner value in an OSC 8 hyperlink to the given URL, if any
+pub(crate) struct Hyperlink<'a, T>(pub(crate) Option<&'a str>, pub(crate) T);
+
+impl<T> fmt::Display for Hyperlink<'_, T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(url) => write!(f, "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, self.1),
+            None => self.1.fmt(f),
+        }
+    }
+}
+
+/// Remove ANSI escape sequences, i.e. colors and hyperlinks, from `s`
+pub(crate) fn strip_escape_sequences(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI sequences, e.g. colors, end with a byte in `@`..=`~`
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC sequences, e.g. hyperlinks, end with BEL or `ESC \`
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    stripped
+}
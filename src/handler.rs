@@ -1,5 +1,6 @@
 use crate::{
-    config::BacktraceFormatter,
+    config::{lib_verbosity, panic_verbosity, BacktraceFormatter, OutputFormat, Verbosity},
+    json,
     section::help::HelpInfo,
     writers::{EnvSection, WriterExt},
     Handler,
@@ -7,6 +8,7 @@ use crate::{
 use backtrace::Backtrace;
 use indenter::{indented, Format};
 use std::fmt::Write;
+use std::sync::PoisonError;
 #[cfg(feature = "capture-spantrace")]
 use tracing_error::{ExtractSpanTrace, SpanTrace};
 
@@ -18,8 +20,29 @@ impl std::fmt::Debug for Handler {
 
 impl Handler {
     /// Return a reference to the captured `Backtrace` type
+    ///
+    /// # Details
+    ///
+    /// Backtraces are captured without resolving their symbols. The first call
+    /// to this method, or the first time the report is formatted, resolves
+    /// them in place.
     pub fn backtrace(&self) -> Option<&Backtrace> {
-        self.backtrace.as_ref()
+        if let Some(backtrace) = self.backtrace.get() {
+            return Some(backtrace);
+        }
+
+        // Holding the lock until the backtrace is set makes concurrent callers
+        // wait for it instead of finding neither backtrace.
+        let mut unresolved = self
+            .unresolved_backtrace
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(mut backtrace) = unresolved.take() {
+            backtrace.resolve();
+            let _ = self.backtrace.set(backtrace);
+        }
+
+        self.backtrace.get()
     }
 
     /// Return a reference to the captured `SpanTrace` type
@@ -29,16 +52,102 @@ impl Handler {
         self.span_trace.as_ref()
     }
 
+    /// The verbosity of this report, which may have been overridden with
+    /// `Section::verbosity`
+    pub(crate) fn verbosity(&self) -> Verbosity {
+        self.verbosity.unwrap_or_else(|| {
+            if std::thread::panicking() {
+                panic_verbosity()
+            } else {
+                lib_verbosity()
+            }
+        })
+    }
+
+    /// The backtrace to display, if it was captured and isn't suppressed
+    fn displayed_backtrace(&self) -> Option<&Backtrace> {
+        if self.suppress_backtrace || self.verbosity == Some(Verbosity::Minimal) {
+            return None;
+        }
+
+        self.backtrace()
+    }
+
+    /// The span trace to display, if it was captured and isn't suppressed
+    #[cfg(feature = "capture-spantrace")]
+    fn displayed_span_trace<'a>(
+        &'a self,
+        error: &'a (dyn std::error::Error + 'static),
+    ) -> Option<&'a SpanTrace> {
+        if self.suppress_span_trace {
+            return None;
+        }
+
+        self.span_trace
+            .as_ref()
+            .or_else(|| get_deepest_spantrace(error))
+    }
+
     pub(crate) fn format_backtrace<'a>(
         &'a self,
         trace: &'a backtrace::Backtrace,
     ) -> BacktraceFormatter<'a> {
         BacktraceFormatter {
             filters: &self.filters,
+            frame_settings: &self.frame_settings,
             inner: trace,
             theme: self.theme,
+            verbosity: self.verbosity(),
         }
     }
+
+    fn debug_json(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        #[cfg(feature = "capture-spantrace")]
+        let errors = eyre::Chain::new(error).filter(|e| e.span_trace().is_none());
+
+        #[cfg(not(feature = "capture-spantrace"))]
+        let errors = eyre::Chain::new(error);
+
+        let mut object = json::Object::new(f)?;
+        object.field("kind", json::Str("error"))?;
+        object.field("errors", json::ErrorChain(errors))?;
+
+        #[cfg(feature = "track-caller")]
+        object.field(
+            "location",
+            json::Nullable(
+                self.location
+                    .map(|loc| json::Location(loc, &self.frame_settings.path_remapper)),
+            ),
+        )?;
+
+        object.field("sections", json::Sections(&self.sections))?;
+
+        #[cfg(feature = "capture-spantrace")]
+        object.field(
+            "span_trace",
+            json::Nullable(
+                self.displayed_span_trace(error)
+                    .map(|span_trace| json::Spans(span_trace, &self.frame_settings.path_remapper)),
+            ),
+        )?;
+
+        match self.displayed_backtrace() {
+            Some(backtrace) => {
+                let fmted_bt = self.format_backtrace(backtrace);
+                let frames = fmted_bt.frames();
+                let filtered_frames = fmted_bt.filter(&frames);
+                object.field("backtrace", json::Frames(&filtered_frames))?;
+            }
+            None => object.field("backtrace", "null")?,
+        }
+
+        object.finish()
+    }
 }
 
 impl eyre::EyreHandler for Handler {
@@ -51,6 +160,10 @@ impl eyre::EyreHandler for Handler {
             return core::fmt::Debug::fmt(error, f);
         }
 
+        if self.output_format.resolve() == OutputFormat::Json {
+            return self.debug_json(error, f);
+        }
+
         #[cfg(feature = "capture-spantrace")]
         let errors = || {
             eyre::Chain::new(error)
@@ -70,13 +183,11 @@ impl eyre::EyreHandler for Handler {
 
         #[cfg(feature = "track-caller")]
         if self.display_location_section {
+            let mut location = separated.ready().header("Location:\n");
             write!(
-                separated.ready(),
+                indented(&mut location.ready()).with_format(Format::Uniform { indentation: "   " }),
                 "{}",
-                crate::SectionExt::header(
-                    crate::fmt::LocationSection(self.location, self.theme),
-                    "Location:"
-                )
+                crate::fmt::LocationSection(self.location, self.theme, &self.frame_settings)
             )?;
         }
 
@@ -97,10 +208,7 @@ impl eyre::EyreHandler for Handler {
         }
 
         #[cfg(feature = "capture-spantrace")]
-        let span_trace = self
-            .span_trace
-            .as_ref()
-            .or_else(|| get_deepest_spantrace(error));
+        let span_trace = self.displayed_span_trace(error);
 
         #[cfg(feature = "capture-spantrace")]
         {
@@ -113,17 +221,14 @@ impl eyre::EyreHandler for Handler {
             }
         }
 
-        if !self.suppress_backtrace {
-            if let Some(backtrace) = self.backtrace.as_ref() {
-                let fmted_bt = self.format_backtrace(backtrace);
+        if let Some(backtrace) = self.displayed_backtrace() {
+            let fmted_bt = self.format_backtrace(backtrace);
 
-                write!(
-                    indented(&mut separated.ready())
-                        .with_format(Format::Uniform { indentation: "  " }),
-                    "{}",
-                    fmted_bt
-                )?;
-            }
+            write!(
+                indented(&mut separated.ready()).with_format(Format::Uniform { indentation: "  " }),
+                "{}",
+                fmted_bt
+            )?;
         }
 
         let f = separated.ready();
@@ -139,9 +244,12 @@ impl eyre::EyreHandler for Handler {
             f = h.ready();
         }
 
-        if self.display_env_section {
+        // The environment variables don't affect reports with an overridden
+        // verbosity
+        if self.display_env_section && !self.suppress_env_section && self.verbosity.is_none() {
             let env_section = EnvSection {
-                bt_captured: &self.backtrace.is_some(),
+                bt_captured: &self.displayed_backtrace().is_some(),
+                verbosity: self.verbosity(),
                 #[cfg(feature = "capture-spantrace")]
                 span_trace,
             };
@@ -150,8 +258,11 @@ impl eyre::EyreHandler for Handler {
         }
 
         #[cfg(feature = "issue-url")]
-        if self.issue_url.is_some() && (*self.issue_filter)(crate::ErrorKind::Recoverable(error)) {
-            let url = self.issue_url.as_ref().unwrap();
+        if let Some(url) = self
+            .issue_url
+            .as_ref()
+            .filter(|_| (*self.issue_filter)(crate::ErrorKind::Recoverable(error)))
+        {
             let mut payload = String::from("Error: ");
             for (n, error) in errors() {
                 writeln!(&mut payload)?;
@@ -159,8 +270,9 @@ impl eyre::EyreHandler for Handler {
             }
 
             let issue_section = crate::section::github::IssueSection::new(url, &payload)
-                .with_backtrace(self.backtrace.as_ref())
-                .with_metadata(&**self.issue_metadata);
+                .with_backtrace(self.backtrace())
+                .with_path_remapper(&self.frame_settings.path_remapper)
+                .with_metadata(&self.issue_metadata);
 
             #[cfg(feature = "capture-spantrace")]
             let issue_section = issue_section.with_span_trace(span_trace);
@@ -0,0 +1,184 @@
+//! A small Rust syntax highlighter for source snippets
+//!
+//! Lines are highlighted independently of each other, so comments and strings
+//! spanning multiple lines are only highlighted on the line they start on.
+use crate::config::Theme;
+use owo_colors::{OwoColorize, Style};
+use std::fmt;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "str", "u8", "u16",
+    "u32", "u64", "u128", "usize",
+];
+
+/// A single line of Rust source, styled with the syntax slots of a `Theme`
+pub(crate) struct Highlighted<'a>(pub(crate) &'a str, pub(crate) Theme);
+
+impl fmt::Display for Highlighted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(line, theme) = self;
+        let mut rest = *line;
+
+        while let Some(c) = rest.chars().next() {
+            let (len, style) = if rest.starts_with("//") {
+                (rest.len(), Some(theme.syntax_comment))
+            } else if rest.starts_with("/*") {
+                let len = rest.find("*/").map_or(rest.len(), |end| end + 2);
+                (len, Some(theme.syntax_comment))
+            } else if let Some(len) = string_literal(rest) {
+                (len, Some(theme.syntax_string))
+            } else if c == '\'' {
+                char_or_lifetime(rest, theme)
+            } else if c.is_alphabetic() || c == '_' {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                let style = if KEYWORDS.contains(&word) {
+                    Some(theme.syntax_keyword)
+                } else if PRIMITIVES.contains(&word) || c.is_uppercase() {
+                    Some(theme.syntax_type)
+                } else {
+                    None
+                };
+                (len, style)
+            } else {
+                (c.len_utf8(), None)
+            };
+
+            let (token, remainder) = rest.split_at(len);
+            match style {
+                Some(style) => write!(f, "{}", token.style(style))?,
+                None => f.write_str(token)?,
+            }
+            rest = remainder;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the length of the string literal at the start of `s`, if any
+fn string_literal(s: &str) -> Option<usize> {
+    let prefix = ["", "b", "r", "br"]
+        .iter()
+        .find(|prefix| s.starts_with(*prefix) && s[prefix.len()..].starts_with('"'))?;
+    let start = prefix.len() + 1;
+
+    // Raw strings don't have escapes
+    if prefix.ends_with('r') {
+        return Some(s[start..].find('"').map_or(s.len(), |end| start + end + 1));
+    }
+
+    let mut chars = s[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(start + i + 1),
+            _ => {}
+        }
+    }
+
+    Some(s.len())
+}
+
+fn char_or_lifetime(s: &str, theme: &Theme) -> (usize, Option<Style>) {
+    let mut chars = s.char_indices().skip(1);
+
+    match chars.next() {
+        Some((_, '\\')) => {
+            let len = s[2..].find('\'').map_or(s.len(), |end| end + 3);
+            (len, Some(theme.syntax_string))
+        }
+        Some((i, c)) => {
+            let after = i + c.len_utf8();
+            if s[after..].starts_with('\'') {
+                (after + 1, Some(theme.syntax_string))
+            } else {
+                let len = s[after..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .map_or(s.len(), |end| after + end);
+                (len, Some(theme.syntax_keyword))
+            }
+        }
+        None => (1, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use owo_colors::style;
+
+    fn theme() -> Theme {
+        Theme::new()
+            .syntax_keyword(style().blue())
+            .syntax_string(style().green())
+            .syntax_comment(style().black())
+            .syntax_type(style().yellow())
+    }
+
+    #[test]
+    fn tokens() {
+        let theme = theme();
+        let highlighted = Highlighted("let s: &str = \"a\\\"b\"; // Str", theme).to_string();
+
+        let expected = format!(
+            "{} s: &{} = {}; {}",
+            "let".style(theme.syntax_keyword),
+            "str".style(theme.syntax_type),
+            "\"a\\\"b\"".style(theme.syntax_string),
+            "// Str".style(theme.syntax_comment),
+        );
+        assert_eq!(highlighted, expected);
+    }
+
+    #[test]
+    fn identifiers_containing_keywords() {
+        assert_eq!(
+            Highlighted("letter + format_in", theme()).to_string(),
+            "letter + format_in"
+        );
+    }
+
+    #[test]
+    fn string_literals() {
+        assert_eq!(string_literal("\"a\" + 1"), Some(3));
+        assert_eq!(string_literal("b\"a\\\"\" + 1"), Some(6));
+        assert_eq!(string_literal("r\"a\\\" + 1"), Some(5));
+        assert_eq!(string_literal("\"unterminated"), Some(13));
+        assert_eq!(string_literal("rust"), None);
+    }
+
+    #[test]
+    fn chars_and_lifetimes() {
+        let theme = theme();
+
+        assert_eq!(
+            char_or_lifetime("'a' ", &theme),
+            (3, Some(theme.syntax_string))
+        );
+        assert_eq!(
+            char_or_lifetime("'\\n' ", &theme),
+            (4, Some(theme.syntax_string))
+        );
+        assert_eq!(
+            char_or_lifetime("'é' ", &theme),
+            (4, Some(theme.syntax_string))
+        );
+        assert_eq!(
+            char_or_lifetime("'static str", &theme),
+            (7, Some(theme.syntax_keyword))
+        );
+        assert_eq!(char_or_lifetime("'", &theme), (1, None));
+    }
+}
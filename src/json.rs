@@ -0,0 +1,284 @@
+//! Minimal JSON serialization used by the machine readable report format
+//!
+//! This intentionally avoids a dependency on `serde`, every value is written
+//! directly to the output as the report is being formatted.
+use crate::{config::Frame, paths::PathRemapper, section::help::HelpInfo};
+use std::fmt::{self, Display, Write};
+#[cfg(feature = "capture-spantrace")]
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+/// Writes the `Display` output of the inner value as an escaped JSON string
+pub(crate) struct Str<T>(pub(crate) T);
+
+impl<T> Display for Str<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        write!(Escaped(&mut *f), "{}", self.0)?;
+        f.write_char('"')
+    }
+}
+
+struct Escaped<W>(W);
+
+impl<W> fmt::Write for Escaped<W>
+where
+    W: fmt::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"' => self.0.write_str("\\\"")?,
+                '\\' => self.0.write_str("\\\\")?,
+                '\n' => self.0.write_str("\\n")?,
+                '\r' => self.0.write_str("\\r")?,
+                '\t' => self.0.write_str("\\t")?,
+                c if (c as u32) < 0x20 => write!(self.0, "\\u{:04x}", c as u32)?,
+                c => self.0.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the inner value, or `null` if there is none
+pub(crate) struct Nullable<T>(pub(crate) Option<T>);
+
+impl<T> Display for Nullable<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+/// Incrementally writes the fields of a JSON object
+pub(crate) struct Object<'a> {
+    out: &'a mut dyn fmt::Write,
+    empty: bool,
+}
+
+impl<'a> Object<'a> {
+    pub(crate) fn new(out: &'a mut dyn fmt::Write) -> Result<Self, fmt::Error> {
+        out.write_char('{')?;
+        Ok(Object { out, empty: true })
+    }
+
+    pub(crate) fn field(&mut self, key: &str, value: impl Display) -> fmt::Result {
+        if !self.empty {
+            self.out.write_char(',')?;
+        }
+        self.empty = false;
+        write!(self.out, "{}:{}", Str(key), value)
+    }
+
+    pub(crate) fn finish(self) -> fmt::Result {
+        self.out.write_char('}')
+    }
+}
+
+/// Incrementally writes the elements of a JSON array
+pub(crate) struct Array<'a> {
+    out: &'a mut dyn fmt::Write,
+    empty: bool,
+}
+
+impl<'a> Array<'a> {
+    pub(crate) fn new(out: &'a mut dyn fmt::Write) -> Result<Self, fmt::Error> {
+        out.write_char('[')?;
+        Ok(Array { out, empty: true })
+    }
+
+    pub(crate) fn element(&mut self, value: impl Display) -> fmt::Result {
+        if !self.empty {
+            self.out.write_char(',')?;
+        }
+        self.empty = false;
+        write!(self.out, "{}", value)
+    }
+
+    pub(crate) fn finish(self) -> fmt::Result {
+        self.out.write_char(']')
+    }
+}
+
+/// A chain of errors rendered as an array of their messages
+pub(crate) struct ErrorChain<I>(pub(crate) I);
+
+impl<'a, I> Display for ErrorChain<I>
+where
+    I: Iterator<Item = &'a (dyn std::error::Error + 'static)> + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut array = Array::new(f)?;
+        for error in self.0.clone() {
+            array.element(Str(error))?;
+        }
+        array.finish()
+    }
+}
+
+/// The sections attached to an error report via the `Section` trait
+pub(crate) struct Sections<'a>(pub(crate) &'a [HelpInfo]);
+
+impl Display for Sections<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut array = Array::new(f)?;
+        for section in self.0 {
+            array.element(Section(section))?;
+        }
+        array.finish()
+    }
+}
+
+struct Section<'a>(&'a HelpInfo);
+
+impl Display for Section<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut object = Object::new(f)?;
+        match self.0 {
+            HelpInfo::Note(note, _) => {
+                object.field("kind", Str("note"))?;
+                object.field("message", Str(note))?;
+            }
+            HelpInfo::Warning(warning, _) => {
+                object.field("kind", Str("warning"))?;
+                object.field("message", Str(warning))?;
+            }
+            HelpInfo::Suggestion(suggestion, _) => {
+                object.field("kind", Str("suggestion"))?;
+                object.field("message", Str(suggestion))?;
+            }
+            HelpInfo::Custom(section) => {
+                object.field("kind", Str("custom"))?;
+                object.field("message", Str(section))?;
+            }
+            HelpInfo::Error(error, _) => {
+                let errors = std::iter::successors(
+                    Some(error.as_ref() as &(dyn std::error::Error + 'static)),
+                    |e| e.source(),
+                );
+
+                object.field("kind", Str("error"))?;
+                object.field("errors", ErrorChain(errors))?;
+            }
+        }
+        object.finish()
+    }
+}
+
+/// The source location of an error or panic
+pub(crate) struct Location<'a>(
+    pub(crate) &'a std::panic::Location<'a>,
+    pub(crate) &'a PathRemapper,
+);
+
+impl Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.1.remap(std::path::Path::new(self.0.file()));
+        let mut object = Object::new(f)?;
+        object.field("file", Str(file.display()))?;
+        object.field("line", self.0.line())?;
+        object.field("column", self.0.column())?;
+        object.finish()
+    }
+}
+
+/// A list of already filtered backtrace frames
+pub(crate) struct Frames<'a>(pub(crate) &'a [&'a Frame]);
+
+impl Display for Frames<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut array = Array::new(f)?;
+        for frame in self.0 {
+            array.element(JsonFrame(frame))?;
+        }
+        array.finish()
+    }
+}
+
+struct JsonFrame<'a>(&'a Frame);
+
+impl Display for JsonFrame<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frame = self.0;
+        let mut object = Object::new(f)?;
+        object.field("n", frame.n)?;
+        object.field("name", Nullable(frame.name.as_ref().map(Str)))?;
+        object.field(
+            "filename",
+            Nullable(frame.filename.as_ref().map(|path| Str(path.display()))),
+        )?;
+        object.field("lineno", Nullable(frame.lineno))?;
+        object.field("colno", Nullable(frame.colno))?;
+        object.field("ip", Str(format_args!("{:#x}", frame.ip)))?;
+        object.field(
+            "module_base",
+            Nullable(frame.module_base.map(|base| Str(format!("{:#x}", base)))),
+        )?;
+        object.finish()
+    }
+}
+
+/// The spans of a captured `SpanTrace`, from innermost to outermost
+#[cfg(feature = "capture-spantrace")]
+pub(crate) struct Spans<'a>(pub(crate) &'a SpanTrace, pub(crate) &'a PathRemapper);
+
+#[cfg(feature = "capture-spantrace")]
+impl Display for Spans<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut array = Array::new(f)?;
+        let mut result = Ok(());
+
+        if self.0.status() == SpanTraceStatus::CAPTURED {
+            self.0.with_spans(|metadata, fields| {
+                result = array.element(Span {
+                    target: metadata.target(),
+                    name: metadata.name(),
+                    fields,
+                    file: metadata
+                        .file()
+                        .map(|file| self.1.remap(std::path::Path::new(file))),
+                    line: metadata.line(),
+                });
+
+                result.is_ok()
+            });
+        }
+
+        result?;
+        array.finish()
+    }
+}
+
+#[cfg(feature = "capture-spantrace")]
+struct Span<'a> {
+    target: &'a str,
+    name: &'a str,
+    fields: &'a str,
+    file: Option<std::borrow::Cow<'a, std::path::Path>>,
+    line: Option<u32>,
+}
+
+#[cfg(feature = "capture-spantrace")]
+impl Display for Span<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut object = Object::new(f)?;
+        object.field("target", Str(self.target))?;
+        object.field("name", Str(self.name))?;
+        object.field("fields", Str(self.fields))?;
+        object.field(
+            "file",
+            Nullable(self.file.as_ref().map(|file| Str(file.display()))),
+        )?;
+        object.field("line", Nullable(self.line))?;
+        object.finish()
+    }
+}
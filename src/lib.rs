@@ -357,7 +357,7 @@
 )]
 #![allow(clippy::try_err)]
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use backtrace::Backtrace;
 pub use eyre;
@@ -365,6 +365,7 @@ pub use eyre;
 pub use eyre::Report;
 #[doc(hidden)]
 pub use eyre::Result;
+use once_cell::sync::OnceCell;
 pub use owo_colors;
 use section::help::HelpInfo;
 #[doc(hidden)]
@@ -376,10 +377,20 @@ use tracing_error::SpanTrace;
 pub use Handler as Context;
 
 pub mod config;
+mod crash_report;
+mod filter;
 mod fmt;
 mod handler;
+#[cfg(feature = "syntax-highlighting")]
+mod highlight;
+mod json;
+mod paths;
 pub(crate) mod private;
 pub mod section;
+mod symbols;
+#[cfg(feature = "detect-workspace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "detect-workspace")))]
+pub mod workspace;
 mod writers;
 
 /// A custom handler type for [`eyre::Report`] which provides colorful error
@@ -396,12 +407,18 @@ mod writers;
 /// [`color_eyre::Result`]: type.Result.html
 pub struct Handler {
     filters: Arc<[Box<config::FilterCallback>]>,
-    backtrace: Option<Backtrace>,
+    frame_settings: Arc<config::FrameSettings>,
+    unresolved_backtrace: Mutex<Option<Backtrace>>,
+    backtrace: OnceCell<Backtrace>,
     suppress_backtrace: bool,
+    verbosity: Option<config::Verbosity>,
     #[cfg(feature = "capture-spantrace")]
     span_trace: Option<SpanTrace>,
+    #[cfg(feature = "capture-spantrace")]
+    suppress_span_trace: bool,
     sections: Vec<HelpInfo>,
     display_env_section: bool,
+    suppress_env_section: bool,
     #[cfg(feature = "track-caller")]
     display_location_section: bool,
     #[cfg(feature = "issue-url")]
@@ -412,6 +429,7 @@ pub struct Handler {
     #[cfg(feature = "issue-url")]
     issue_filter: std::sync::Arc<config::IssueFilterCallback>,
     theme: crate::config::Theme,
+    output_format: config::OutputFormat,
     #[cfg(feature = "track-caller")]
     location: Option<&'static std::panic::Location<'static>>,
 }
@@ -0,0 +1,74 @@
+//! Rewriting of source paths before they are displayed or looked up
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+/// Applies the path prefix remapping rules configured on the `HookBuilder`
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PathRemapper {
+    rules: Vec<(PathBuf, PathBuf)>,
+    shorten: bool,
+    home: Option<PathBuf>,
+}
+
+impl PathRemapper {
+    pub(crate) fn new(rules: Vec<(PathBuf, PathBuf)>, shorten: bool) -> Self {
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .filter(|home| !home.is_empty())
+            .map(PathBuf::from);
+
+        PathRemapper {
+            rules,
+            shorten,
+            home,
+        }
+    }
+
+    /// Rewrite `path` with the first rule whose prefix matches it.
+    ///
+    /// The remapped path is used everywhere, including for looking up source
+    /// snippets and in machine readable output.
+    pub(crate) fn remap<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        self.rules
+            .iter()
+            .find_map(|(from, to)| path.strip_prefix(from).ok().map(|rest| to.join(rest)))
+            .map_or(Cow::Borrowed(path), Cow::Owned)
+    }
+
+    /// Shorten an already remapped `path` for display, if enabled.
+    ///
+    /// Sources of registry crates are shown relative to the registry, e.g.
+    /// `crate-x.y.z/src/lib.rs`, and the home directory is replaced with `~`.
+    pub(crate) fn shorten(&self, path: &Path) -> String {
+        if !self.shorten {
+            return path.display().to_string();
+        }
+
+        let components: Vec<_> = path.components().collect();
+        let registry = components.windows(3).position(|window| {
+            window[0].as_os_str() == ".cargo"
+                && window[1].as_os_str() == "registry"
+                && window[2].as_os_str() == "src"
+        });
+
+        // Skip `.cargo/registry/src/<index>/`
+        if let Some(start) = registry.filter(|start| start + 4 < components.len()) {
+            return components[start + 4..]
+                .iter()
+                .collect::<PathBuf>()
+                .display()
+                .to_string();
+        }
+
+        match self
+            .home
+            .as_ref()
+            .and_then(|home| path.strip_prefix(home).ok())
+        {
+            Some(rest) => Path::new("~").join(rest).display().to_string(),
+            None => path.display().to_string(),
+        }
+    }
+}
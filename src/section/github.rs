@@ -1,6 +1,6 @@
-use crate::writers::DisplayExt;
-use backtrace::Backtrace;
-use std::{fmt, panic::Location};
+use crate::{paths::PathRemapper, writers::DisplayExt};
+use backtrace::{Backtrace, BacktraceFmt, BytesOrWideString, PrintFmt};
+use std::{fmt, panic::Location, path::Path};
 #[cfg(feature = "capture-spantrace")]
 use tracing_error::SpanTrace;
 use url::Url;
@@ -15,6 +15,7 @@ pub(crate) struct IssueSection<'a> {
     #[cfg(feature = "capture-spantrace")]
     span_trace: Option<&'a SpanTrace>,
     metadata: &'a [(String, Display<'a>)],
+    paths: Option<&'a PathRemapper>,
 }
 
 impl<'a> IssueSection<'a> {
@@ -27,6 +28,7 @@ impl<'a> IssueSection<'a> {
             #[cfg(feature = "capture-spantrace")]
             span_trace: None,
             metadata: &[],
+            paths: None,
         }
     }
 
@@ -46,6 +48,11 @@ impl<'a> IssueSection<'a> {
         self
     }
 
+    pub(crate) fn with_path_remapper(mut self, paths: &'a PathRemapper) -> Self {
+        self.paths = Some(paths);
+        self
+    }
+
     pub(crate) fn with_metadata(mut self, metadata: &'a [(String, Display<'a>)]) -> Self {
         self.metadata = metadata;
         self
@@ -54,9 +61,12 @@ impl<'a> IssueSection<'a> {
 
 impl fmt::Display for IssueSection<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let location = self
-            .location
-            .map(|loc| ("location".to_string(), Box::new(loc) as _));
+        let location = self.location.map(|loc| {
+            let file = Path::new(loc.file());
+            let file = self.paths.map_or(file.into(), |paths| paths.remap(file));
+            let loc = format!("{}:{}:{}", file.display(), loc.line(), loc.column());
+            ("location".to_string(), Box::new(loc) as _)
+        });
         let metadata = self.metadata.iter().chain(location.as_ref());
         let metadata = MetadataSection { metadata }.to_string();
         let mut body = Body::new();
@@ -78,7 +88,7 @@ impl fmt::Display for IssueSection<'_> {
             body.push_section(
                 "Backtrace",
                 Collapsed(ConsoleSection(
-                    DisplayFromDebug(bt).with_header("Backtrace:\n"),
+                    RemappedBacktrace(bt, self.paths).with_header("Backtrace:\n"),
                 )),
             )?;
         }
@@ -178,13 +188,26 @@ where
     }
 }
 
-struct DisplayFromDebug<T>(T);
+/// Formats a backtrace like its `Debug` impl, with remapped source paths
+struct RemappedBacktrace<'a>(&'a Backtrace, Option<&'a PathRemapper>);
 
-impl<T> fmt::Display for DisplayFromDebug<T>
-where
-    T: fmt::Debug,
-{
+impl fmt::Display for RemappedBacktrace<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        // The path printing callback has to outlive the formatter
+        let paths = self.1.cloned();
+        let mut print_path = move |fmt: &mut fmt::Formatter<'_>, path: BytesOrWideString<'_>| {
+            let path = path.into_path_buf();
+            match &paths {
+                Some(paths) => fmt::Display::fmt(&paths.remap(&path).display(), fmt),
+                None => fmt::Display::fmt(&path.display(), fmt),
+            }
+        };
+
+        let mut f = BacktraceFmt::new(f, PrintFmt::Short, &mut print_path);
+        f.add_context()?;
+        for frame in self.0.frames() {
+            f.frame().backtrace_frame(frame)?;
+        }
+        f.finish()
     }
 }
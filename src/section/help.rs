@@ -1,6 +1,6 @@
 //! Provides an extension trait for attaching `Section` to error reports.
 use crate::{
-    config::Theme,
+    config::{Theme, Verbosity},
     eyre::{Report, Result},
     Section,
 };
@@ -8,6 +8,7 @@ use indenter::indented;
 use owo_colors::OwoColorize;
 use std::fmt::Write;
 use std::fmt::{self, Display};
+use std::sync::PoisonError;
 
 impl Section for Report {
     type Return = Report;
@@ -150,6 +151,42 @@ impl Section for Report {
 
         self
     }
+
+    fn verbosity(mut self, verbosity: Verbosity) -> Self::Return {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            if verbosity != Verbosity::Minimal && handler.backtrace.get().is_none() {
+                handler
+                    .unresolved_backtrace
+                    .get_mut()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get_or_insert_with(backtrace::Backtrace::new_unresolved);
+            }
+            handler.verbosity = Some(verbosity);
+        }
+
+        self
+    }
+
+    #[cfg_attr(
+        not(feature = "capture-spantrace"),
+        allow(unused_mut, unused_variables)
+    )]
+    fn suppress_span_trace(mut self, suppress: bool) -> Self::Return {
+        #[cfg(feature = "capture-spantrace")]
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            handler.suppress_span_trace = suppress;
+        }
+
+        self
+    }
+
+    fn suppress_env_section(mut self, suppress: bool) -> Self::Return {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            handler.suppress_env_section = suppress;
+        }
+
+        self
+    }
 }
 
 impl<T, E> Section for Result<T, E>
@@ -247,6 +284,21 @@ where
         self.map_err(|error| error.into())
             .map_err(|report| report.suppress_backtrace(suppress))
     }
+
+    fn verbosity(self, verbosity: Verbosity) -> Self::Return {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.verbosity(verbosity))
+    }
+
+    fn suppress_span_trace(self, suppress: bool) -> Self::Return {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.suppress_span_trace(suppress))
+    }
+
+    fn suppress_env_section(self, suppress: bool) -> Self::Return {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.suppress_env_section(suppress))
+    }
 }
 
 pub(crate) enum HelpInfo {
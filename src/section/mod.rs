@@ -1,5 +1,5 @@
 //! Helpers for adding custom sections to error reports
-use crate::writers::WriterExt;
+use crate::{config::Verbosity, writers::WriterExt};
 use std::fmt::{self, Display};
 
 #[cfg(feature = "issue-url")]
@@ -321,9 +321,35 @@ pub trait Section: crate::private::Sealed {
     /// Useful for reporting "unexceptional" errors for which a backtrace
     /// isn't really necessary.
     fn suppress_backtrace(self, suppress: bool) -> Self::Return;
+
+    /// Override the verbosity of this report, regardless of the
+    /// `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` environment variables.
+    ///
+    /// Useful for printing expected errors tersely with `Verbosity::Minimal`,
+    /// while internal errors are printed with `Verbosity::Full`. If no
+    /// backtrace was captured when the report was created, one is captured
+    /// when this method is called. The report doesn't suggest environment
+    /// variables to change its verbosity.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::{config::Verbosity, eyre::eyre, Section};
+    ///
+    /// let report = eyre!("invariant violated").verbosity(Verbosity::Full);
+    /// ```
+    fn verbosity(self, verbosity: Verbosity) -> Self::Return;
+
+    /// Whether to suppress printing of the captured `SpanTrace` (if any).
+    fn suppress_span_trace(self, suppress: bool) -> Self::Return;
+
+    /// Whether to suppress printing of the section explaining which
+    /// environment variables control the report's verbosity.
+    fn suppress_env_section(self, suppress: bool) -> Self::Return;
 }
 
 /// Trait for printing a panic error message for the given PanicInfo
+#[allow(deprecated)]
 pub trait PanicMessage: Send + Sync + 'static {
     /// Display trait equivalent for implementing the display logic
     fn display(&self, pi: &std::panic::PanicInfo<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result;
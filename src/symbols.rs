@@ -0,0 +1,145 @@
+//! Simplification of symbol names for display
+use crate::config::strip_crate_disambiguators;
+
+/// Crates whose fully qualified type and trait paths are shortened to the bare
+/// type or trait name
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// Simplify a symbol name, without its hash suffix, for display.
+///
+/// Generic parameter lists are collapsed to `<…>`, paths of types and traits
+/// from `std`, `core` and `alloc` are reduced to their name and chains of
+/// closures are collapsed into one, e.g.
+/// `<alloc::boxed::Box<F,A> as core::ops::function::FnOnce<Args>>::call_once::{{closure}}`
+/// becomes `<Box<…> as FnOnce<…>>::call_once::{{closure}}`.
+pub(crate) fn simplify(name: &str) -> String {
+    let name = strip_crate_disambiguators(name);
+    let mut simplified = String::with_capacity(name.len());
+    let mut rest = &*name;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' && simplified.ends_with(is_path_char) {
+            // Generic parameters, as opposed to a qualified path like `<T as Trait>`
+            simplified.push_str("<…>");
+            rest = &rest[generics_len(rest)..];
+        } else if is_path_char(c) {
+            let len = rest.find(|c| !is_path_char(c)).unwrap_or(rest.len());
+            push_path(&mut simplified, &rest[..len]);
+            rest = &rest[len..];
+        } else {
+            simplified.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    collapse_closures(&mut simplified);
+    simplified
+}
+
+fn is_path_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == ':'
+}
+
+/// The length of the generic parameter list at the start of `s`, including
+/// the angle brackets
+fn generics_len(s: &str) -> usize {
+    let mut depth = 0;
+    let mut prev = None;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            // Skip the arrow of `Fn() -> T`
+            '>' if prev != Some('-') => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        prev = Some(c);
+    }
+
+    s.len()
+}
+
+/// Push `path`, without the modules leading up to the first type or trait if
+/// it is a path into `std`, `core` or `alloc`
+fn push_path(out: &mut String, path: &str) {
+    let is_std_path = path
+        .split_once("::")
+        .is_some_and(|(krate, _)| STD_CRATES.contains(&krate));
+
+    let first_type = path
+        .match_indices("::")
+        .map(|(i, sep)| i + sep.len())
+        .find(|&i| path[i..].starts_with(char::is_uppercase));
+
+    match first_type.filter(|_| is_std_path) {
+        Some(start) => out.push_str(&path[start..]),
+        None => out.push_str(path),
+    }
+}
+
+/// Collapse chains of nested closures, e.g. `::{{closure}}::{{closure}}`,
+/// into a single closure
+fn collapse_closures(name: &mut String) {
+    const CLOSURE: &str = "::{{closure}}";
+
+    let nested = CLOSURE.repeat(2);
+    while name.contains(&nested) {
+        *name = name.replace(&nested, CLOSURE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn std_paths_and_generics() {
+        assert_eq!(
+            simplify("<alloc::boxed::Box<F,A> as core::ops::function::FnOnce<Args>>::call_once"),
+            "<Box<…> as FnOnce<…>>::call_once"
+        );
+        assert_eq!(
+            simplify("std::collections::hash::map::HashMap<K,V,S>::insert"),
+            "HashMap<…>::insert"
+        );
+        assert_eq!(simplify("std::rt::lang_start"), "std::rt::lang_start");
+    }
+
+    #[test]
+    fn own_paths_are_kept() {
+        assert_eq!(simplify("my_app::server::run"), "my_app::server::run");
+        assert_eq!(
+            simplify("<my_app::Server as core::future::future::Future>::poll"),
+            "<my_app::Server as Future>::poll"
+        );
+    }
+
+    #[test]
+    fn fn_arrows_in_generics() {
+        assert_eq!(
+            simplify("my_app::call<fn() -> u32>::{{closure}}"),
+            "my_app::call<…>::{{closure}}"
+        );
+    }
+
+    #[test]
+    fn nested_closures() {
+        assert_eq!(
+            simplify("my_app::main::{{closure}}::{{closure}}::{{closure}}"),
+            "my_app::main::{{closure}}"
+        );
+    }
+
+    #[test]
+    fn crate_disambiguators() {
+        assert_eq!(
+            simplify("<core[c1f1a4ba060b9bfa]::ops::function::FnOnce<()>>::call_once"),
+            "<FnOnce<…>>::call_once"
+        );
+    }
+}
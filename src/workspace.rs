@@ -0,0 +1,233 @@
+//! Build script support for treating the packages of your cargo workspace as
+//! your own code
+//!
+//! # Examples
+//!
+//! In the build script of your crate:
+//!
+//! ```rust,no_run
+//! color_eyre::workspace::detect_workspace();
+//! ```
+//!
+//! And where the hooks are installed:
+//!
+//! ```rust,ignore
+//! color_eyre::config::HookBuilder::default()
+//!     .workspace_members(env!("COLOR_EYRE_WORKSPACE_MEMBERS"))
+//!     .install()
+//!     .unwrap();
+//! ```
+use std::path::{Path, PathBuf};
+
+/// Detect the packages of the cargo workspace of the crate being built, for
+/// `HookBuilder::workspace_members`.
+///
+/// # Details
+///
+/// This has to be called from a build script. It sets the
+/// `COLOR_EYRE_WORKSPACE_MEMBERS` environment variable for the compilation of
+/// the crate to the directories of the workspace members, so the list is
+/// embedded in the binary and doesn't depend on the manifests being
+/// available where it runs.
+///
+/// Like cargo, the nearest `Cargo.toml` with a `[workspace]` table above
+/// `CARGO_MANIFEST_DIR` is used, and only if its `members` (minus its
+/// `exclude`) include the crate. Otherwise the crate is the only member.
+///
+/// # Panics
+///
+/// Panics if `CARGO_MANIFEST_DIR` isn't set, i.e. if not called from a build
+/// script.
+pub fn detect_workspace() {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .expect("detect_workspace must be called from a build script");
+    let members = std::env::join_paths(workspace_members(manifest_dir.into()))
+        .expect("workspace paths can't contain the path list separator");
+
+    println!(
+        "cargo:rustc-env=COLOR_EYRE_WORKSPACE_MEMBERS={}",
+        members.to_string_lossy()
+    );
+}
+
+/// Find the package directories of the cargo workspace containing
+/// `manifest_dir`
+fn workspace_members(manifest_dir: PathBuf) -> Vec<PathBuf> {
+    let workspace = manifest_dir.ancestors().find_map(|dir| {
+        let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+        Some((dir, parse_workspace(&manifest)?))
+    });
+
+    if let Some((root, workspace)) = workspace {
+        let excluded = |dir: &Path| {
+            workspace
+                .exclude
+                .iter()
+                .any(|path| dir.starts_with(root.join(path)))
+        };
+        let mut packages: Vec<PathBuf> = workspace
+            .members
+            .iter()
+            .flat_map(|member| expand_member(root, member))
+            .filter(|dir| !excluded(dir))
+            .collect();
+        if workspace.root_package {
+            packages.push(root.to_path_buf());
+        }
+
+        if packages.contains(&manifest_dir) {
+            return packages;
+        }
+    }
+
+    vec![manifest_dir]
+}
+
+/// The `[workspace]` table of a `Cargo.toml`
+#[derive(Debug, PartialEq)]
+struct Workspace {
+    members: Vec<String>,
+    exclude: Vec<String>,
+    /// Whether the manifest also has a `[package]` table
+    root_package: bool,
+}
+
+/// Parse the `[workspace]` table of a `Cargo.toml`, if it has one
+fn parse_workspace(manifest: &str) -> Option<Workspace> {
+    let manifest: toml::Table = manifest.parse().ok()?;
+    let workspace = manifest.get("workspace")?.as_table()?;
+
+    Some(Workspace {
+        members: string_array(workspace, "members"),
+        exclude: string_array(workspace, "exclude"),
+        root_package: manifest.contains_key("package"),
+    })
+}
+
+/// The strings of the array `key = [...]` in a table of a `Cargo.toml`
+fn string_array(table: &toml::Table, key: &str) -> Vec<String> {
+    table
+        .get(key)
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+        .map(String::from)
+        .collect()
+}
+
+/// The package directories matching the workspace `member` glob
+fn expand_member(root: &Path, member: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    for component in Path::new(member).components() {
+        let component = component.as_os_str().to_string_lossy();
+        if component == "." {
+            continue;
+        }
+
+        if !component.contains(['*', '?']) {
+            dirs = dirs.into_iter().map(|dir| dir.join(&*component)).collect();
+            continue;
+        }
+
+        dirs = dirs
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                crate::filter::glob_match(&component, &entry.file_name().to_string_lossy())
+            })
+            .map(|entry| entry.path())
+            .collect();
+    }
+
+    dirs.retain(|dir| dir.join("Cargo.toml").is_file());
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_with_comments() {
+        let manifest = r#"
+[workspace]
+members = [
+    "a", # core
+    "b",
+]
+"#;
+
+        let workspace = parse_workspace(manifest).unwrap();
+        assert_eq!(workspace.members, ["a", "b"]);
+        assert!(workspace.exclude.is_empty());
+        assert!(!workspace.root_package);
+    }
+
+    #[test]
+    fn brackets_and_commas_in_strings() {
+        let manifest = r#"
+[package]
+name = "root"
+
+[workspace]
+# members = ["commented-out"]
+members = ["crates/[a]", 'crates/b,c']
+exclude = ["crates/old"]
+"#;
+
+        assert_eq!(
+            parse_workspace(manifest),
+            Some(Workspace {
+                members: vec!["crates/[a]".into(), "crates/b,c".into()],
+                exclude: vec!["crates/old".into()],
+                root_package: true,
+            })
+        );
+    }
+
+    #[test]
+    fn dotted_workspace_keys() {
+        let manifest = "workspace.members = [\"a\"]\n";
+
+        assert_eq!(parse_workspace(manifest).unwrap().members, ["a"]);
+    }
+
+    #[test]
+    fn no_workspace() {
+        assert_eq!(parse_workspace("[package]\nname = \"app\"\n"), None);
+        assert_eq!(parse_workspace("[workspace\n"), None);
+    }
+
+    #[test]
+    fn nearest_workspace_members() {
+        use std::fs;
+
+        // An unrelated workspace containing a workspace with the crate
+        let outer =
+            std::env::temp_dir().join(format!("color-eyre-workspace-{}", std::process::id()));
+        let root = outer.join("project");
+        let member = root.join("crates").join("app");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(
+            outer.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"tools\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"project\"\n\n[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+
+        let members = workspace_members(member.clone());
+        let not_a_member = workspace_members(outer.join("app"));
+        fs::remove_dir_all(&outer).unwrap();
+
+        assert_eq!(members, [member, root]);
+        assert_eq!(not_a_member, [outer.join("app")]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::{lib_verbosity, panic_verbosity, Verbosity};
+use crate::config::Verbosity;
 use fmt::Write;
 use std::fmt::{self, Display};
 #[cfg(feature = "capture-spantrace")]
@@ -28,11 +28,13 @@ impl<W> WriterExt for W {
     }
 }
 
+#[cfg(feature = "issue-url")]
 pub(crate) trait DisplayExt: Sized + Display {
     fn with_header<H: Display>(self, header: H) -> Header<Self, H>;
     fn with_footer<F: Display>(self, footer: F) -> Footer<Self, F>;
 }
 
+#[cfg(feature = "issue-url")]
 impl<T> DisplayExt for T
 where
     T: Display,
@@ -80,11 +82,13 @@ where
     }
 }
 
+#[cfg(feature = "issue-url")]
 pub(crate) struct FooterWriter<W> {
     inner: W,
     had_output: bool,
 }
 
+#[cfg(feature = "issue-url")]
 impl<W> fmt::Write for FooterWriter<W>
 where
     W: fmt::Write,
@@ -98,6 +102,7 @@ where
     }
 }
 
+#[cfg(feature = "issue-url")]
 #[allow(explicit_outlives_requirements)]
 pub(crate) struct Footer<B, H>
 where
@@ -108,6 +113,7 @@ where
     footer: H,
 }
 
+#[cfg(feature = "issue-url")]
 impl<B, H> fmt::Display for Footer<B, H>
 where
     B: Display,
@@ -129,6 +135,7 @@ where
     }
 }
 
+#[cfg(feature = "issue-url")]
 #[allow(explicit_outlives_requirements)]
 pub(crate) struct Header<B, H>
 where
@@ -139,6 +146,7 @@ where
     h: H,
 }
 
+#[cfg(feature = "issue-url")]
 impl<B, H> fmt::Display for Header<B, H>
 where
     B: Display,
@@ -174,31 +182,43 @@ impl fmt::Display for FormattedSpanTrace<'_> {
 
 pub(crate) struct EnvSection<'a> {
     pub(crate) bt_captured: &'a bool,
+    pub(crate) verbosity: Verbosity,
     #[cfg(feature = "capture-spantrace")]
     pub(crate) span_trace: Option<&'a SpanTrace>,
 }
 
 impl fmt::Display for EnvSection<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let v = if std::thread::panicking() {
-            panic_verbosity()
-        } else {
-            lib_verbosity()
-        };
-        write!(f, "{}", BacktraceOmited(!self.bt_captured))?;
+        let v = self.verbosity;
+        // Setting `RUST_BACKTRACE` has no effect while the verbosity is
+        // overridden, so don't suggest it
+        let overridden = crate::config::verbosity_override().is_some();
+        let started = *self.bt_captured || !overridden;
 
         let mut separated = HeaderWriter {
             inner: &mut *f,
             header: &"\n",
             started: false,
         };
-        write!(&mut separated.ready(), "{}", SourceSnippets(v))?;
+        if started {
+            write!(
+                &mut separated.in_progress(),
+                "{}",
+                BacktraceOmited(!self.bt_captured)
+            )?;
+            if !overridden {
+                write!(&mut separated.ready(), "{}", SourceSnippets(v))?;
+            }
+        }
         #[cfg(feature = "capture-spantrace")]
-        write!(
-            &mut separated.ready(),
-            "{}",
-            SpanTraceOmited(self.span_trace)
-        )?;
+        {
+            let mut f = if started {
+                separated.ready()
+            } else {
+                separated.in_progress()
+            };
+            write!(&mut f, "{}", SpanTraceOmited(self.span_trace))?;
+        }
         Ok(())
     }
 }
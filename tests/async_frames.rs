@@ -0,0 +1,69 @@
+use color_eyre::{config::HookBuilder, eyre::eyre, Report};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+/// The symbol names seen by frame filters
+static FILTERED_NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        // Poll through `<Pin<P> as Future>::poll`, like most executors do
+        if let Poll::Ready(output) = Pin::new(&mut future).poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[inline(never)]
+async fn inner() -> Report {
+    eyre!("error occured")
+}
+
+#[inline(never)]
+async fn outer() -> Report {
+    std::hint::black_box(inner().await)
+}
+
+#[test]
+fn async_frames() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    HookBuilder::default()
+        .add_async_frame_filters()
+        .add_frame_filter(Box::new(|frames| {
+            let names = frames.iter().filter_map(|frame| frame.name.clone());
+            FILTERED_NAMES.lock().unwrap().extend(names);
+        }))
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", block_on(outer()));
+    assert!(report.contains("async_frames::inner"));
+    assert!(report.contains("async_frames::outer"));
+    assert!(!report.contains("{{closure}}::h"));
+    assert!(!report.contains("as core::future::future::Future>::poll"));
+
+    // Only the displayed names are relabeled
+    let is_async_body = |name: &String| {
+        name.starts_with("async_frames::inner::{{closure}}")
+            || name.starts_with("async_frames::inner::{closure#0}")
+    };
+    assert!(FILTERED_NAMES.lock().unwrap().iter().any(is_async_body));
+}
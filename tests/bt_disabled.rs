@@ -1,4 +1,4 @@
-use color_eyre::eyre;
+use color_eyre::{eyre, Section};
 use eyre::eyre;
 
 #[test]
@@ -12,4 +12,8 @@ fn disabled() {
 
     let report = format!("{:?}", report);
     assert!(!report.contains("RUST_BACKTRACE"));
+
+    // Not suppressing the section of a report doesn't override the builder
+    let report = format!("{:?}", eyre!("error occured").suppress_env_section(false));
+    assert!(!report.contains("RUST_BACKTRACE"));
 }
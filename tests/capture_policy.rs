@@ -0,0 +1,39 @@
+use color_eyre::{
+    config::{CapturePolicy, HookBuilder},
+    eyre::{eyre, Report},
+    Handler,
+};
+use std::io;
+
+fn backtrace_captured(report: &Report) -> bool {
+    report
+        .handler()
+        .downcast_ref::<Handler>()
+        .unwrap()
+        .backtrace()
+        .is_some()
+}
+
+#[test]
+fn capture_policy() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    HookBuilder::default()
+        .capture_policy(|error| {
+            let would_block = error
+                .downcast_ref::<io::Error>()
+                .is_some_and(|error| error.kind() == io::ErrorKind::WouldBlock);
+
+            CapturePolicy {
+                backtrace: !would_block,
+                span_trace: !would_block,
+            }
+        })
+        .install()
+        .unwrap();
+
+    let report = Report::new(io::Error::from(io::ErrorKind::WouldBlock));
+    assert!(!backtrace_captured(&report));
+
+    let report = eyre!("error occured");
+    assert!(backtrace_captured(&report));
+}
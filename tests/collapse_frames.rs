@@ -0,0 +1,47 @@
+use color_eyre::eyre::{eyre, Report};
+
+#[inline(never)]
+fn recurse(depth: usize) -> Report {
+    if depth == 0 {
+        eyre!("error occured")
+    } else {
+        let report = recurse(depth - 1);
+        std::hint::black_box(report)
+    }
+}
+
+#[inline(never)]
+fn recurse_with_gaps(depth: usize) -> Report {
+    if depth == 0 {
+        eyre!("error occured")
+    } else {
+        let report = recurse_with_gaps(depth - 1);
+        std::hint::black_box(report)
+    }
+}
+
+#[test]
+fn collapsed() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    color_eyre::config::HookBuilder::default()
+        .add_frame_filter(Box::new(|frames| {
+            frames.retain(|frame| {
+                let recursion = frame
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| name.contains("collapse_frames::recurse_with_gaps"));
+                !recursion || frame.n % 2 == 0
+            })
+        }))
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", recurse(50));
+    assert!(report.contains("repeat ⋮"));
+    assert!(report.matches("collapse_frames::recurse").count() < 10);
+
+    // Runs spanning filtered frames aren't collapsed
+    let report = format!("{:?}", recurse_with_gaps(20));
+    assert!(!report.contains("repeat ⋮"));
+    assert!(report.matches("collapse_frames::recurse_with_gaps").count() >= 8);
+}
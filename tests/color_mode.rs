@@ -0,0 +1,17 @@
+use color_eyre::{config::ColorMode, eyre::eyre, Section};
+
+#[test]
+fn never() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    color_eyre::config::HookBuilder::default()
+        .color_mode(ColorMode::Never)
+        .install()
+        .unwrap();
+
+    let report = eyre!("error occured").note("note").warning("warning");
+
+    let report = format!("{:?}", report);
+    assert!(report.contains("BACKTRACE"));
+    assert!(!report.contains('\x1b'));
+}
@@ -0,0 +1,54 @@
+use color_eyre::config::{HookBuilder, PanicWriter};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn crash_report() {
+    std::env::remove_var("RUST_BACKTRACE");
+    std::env::remove_var("COLORBT_FORMAT");
+
+    let dir = std::env::temp_dir().join(format!("color-eyre-crash-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let console = Arc::new(Mutex::new(String::new()));
+    let console_report = console.clone();
+    HookBuilder::default()
+        .crash_report_dir(&dir)
+        .crash_report_retention(2)
+        .crash_report_json(true)
+        .panic_writer(
+            PanicWriter::callback(move |report| {
+                *console_report.lock().unwrap() = report.to_owned()
+            })
+            .colored(false),
+        )
+        .install()
+        .unwrap();
+
+    for _ in 0..3 {
+        std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    assert_eq!(files.len(), 4, "{:?}", files);
+
+    let saved = files.last().unwrap().with_extension("txt");
+    let console = console.lock().unwrap().clone();
+    assert!(console.contains(&format!("Full report saved to {}", saved.display())));
+    assert!(!console.contains(" BACKTRACE "));
+
+    let text = std::fs::read_to_string(&saved).unwrap();
+    assert!(text.contains("Message:  expected panic\n"));
+    assert!(text.contains(" BACKTRACE "));
+    assert!(!text.contains('\u{1b}'));
+    assert!(!text.contains("RUST_BACKTRACE"));
+
+    let json = std::fs::read_to_string(saved.with_extension("json")).unwrap();
+    assert!(json.starts_with(r#"{"kind":"panic","message":"expected panic""#));
+    assert!(json.contains(r#""backtrace":[{"n":"#));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,26 @@
+use color_eyre::eyre::{eyre, Report};
+
+#[inline(never)]
+fn uninteresting() -> Report {
+    std::hint::black_box(eyre!("error occured"))
+}
+
+#[test]
+fn env_filter() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    std::env::set_var("COLORBT_FILTER", "-env_filter::uninteresting, +eyre::");
+    color_eyre::install().unwrap();
+
+    let report = format!("{:?}", uninteresting());
+    assert!(!report.contains("env_filter::uninteresting"));
+    assert!(!report.contains("hidden (eyre"));
+    assert!(report.contains("env_filter::env_filter"));
+
+    std::env::set_var("COLORBT_FILTER", "-env_filter::*");
+    let report = format!("{:?}", uninteresting());
+    assert!(!report.contains("env_filter::"));
+
+    std::env::set_var("COLORBT_FILTER", "-*/tests/env_filter.rs");
+    let report = format!("{:?}", uninteresting());
+    assert!(!report.contains("env_filter::"));
+}
@@ -0,0 +1,16 @@
+use color_eyre::eyre::eyre;
+
+#[test]
+fn frame_addresses() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "full");
+    color_eyre::install().unwrap();
+
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(report.contains("at address 0x"));
+    // The location section and the frames include the column
+    #[cfg(feature = "track-caller")]
+    assert!(report.contains(
+        "\u{1b}[35mtests/frame_addresses.rs\u{1b}[0m:\u{1b}[35m8\u{1b}[0m:\u{1b}[35m34\u{1b}[0m"
+    ));
+    assert!(report.contains("frame_addresses.rs\u{1b}[0m:\u{1b}[35m8\u{1b}[0m:\u{1b}[35m"));
+}
@@ -0,0 +1,34 @@
+use color_eyre::{
+    config::{FrameFilter, FrameMatcher, HookBuilder},
+    eyre::{eyre, Report},
+};
+
+#[inline(never)]
+fn first() -> Report {
+    std::hint::black_box(second())
+}
+
+#[inline(never)]
+fn second() -> Report {
+    std::hint::black_box(eyre!("error occured"))
+}
+
+#[test]
+fn frame_filter() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    let hide_test_file = FrameFilter::new()
+        .hide(FrameMatcher::glob("*tests/frame_filter.rs"))
+        .show(FrameMatcher::prefix("frame_filter::second"));
+    let cutoff = FrameFilter::new().drop_below(FrameMatcher::crate_name("frame_filter"));
+
+    HookBuilder::default()
+        .add_frame_filter(hide_test_file.chain(cutoff).into())
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", first());
+    assert!(report.contains("frame_filter::second"));
+    assert!(!report.contains("frame_filter::first"));
+    assert!(!report.contains("core::ops::function::FnOnce::call_once"));
+}
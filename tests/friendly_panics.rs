@@ -0,0 +1,39 @@
+use color_eyre::config::{FriendlyPanicMessage, HookBuilder, PanicWriter};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn friendly_panics() {
+    std::env::remove_var("RUST_BACKTRACE");
+    std::env::remove_var("COLORBT_FORMAT");
+
+    let console = Arc::new(Mutex::new(String::new()));
+    let console_report = console.clone();
+    HookBuilder::default()
+        .friendly_panics(
+            FriendlyPanicMessage::new("my-app")
+                .version("1.2.3")
+                .support_url("https://example.com/issues"),
+        )
+        .panic_writer(
+            PanicWriter::callback(move |report| {
+                *console_report.lock().unwrap() = report.to_owned()
+            })
+            .colored(false),
+        )
+        .install()
+        .unwrap();
+
+    std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+    let report = console.lock().unwrap().clone();
+    assert_eq!(
+        report,
+        "my-app 1.2.3 crashed unexpectedly, sorry about that!\n\
+         Please report this problem at https://example.com/issues\n"
+    );
+
+    std::env::set_var("RUST_BACKTRACE", "1");
+    std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+    let report = console.lock().unwrap().clone();
+    assert!(report.starts_with("The application panicked (crashed).\nMessage:  expected panic\n"));
+    assert!(report.contains(" BACKTRACE "));
+}
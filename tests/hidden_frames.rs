@@ -0,0 +1,23 @@
+use color_eyre::{config::HookBuilder, eyre::eyre};
+
+#[test]
+fn hidden_frames_are_summarized_by_crate() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    // Pretend this test is part of the registry package `my-app`
+    HookBuilder::default()
+        .remap_path_prefix(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests"),
+            "/opt/.cargo/registry/src/index.crates.io-0/my-app-1.0.0/tests",
+        )
+        .add_own_crate("my_app")
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(report.contains("frames hidden (eyre ×"));
+    assert!(report.contains("color_eyre ×"));
+
+    // The crate name of registry packages is their identifier
+    assert!(report.contains("\u{1b}[91mhidden_frames::hidden_frames_are_summarized_by_crate"));
+}
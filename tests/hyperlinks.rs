@@ -0,0 +1,23 @@
+use color_eyre::{
+    config::{HookBuilder, Hyperlinks},
+    eyre::eyre,
+};
+
+#[test]
+fn hyperlinks() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    HookBuilder::default()
+        .hyperlinks(Hyperlinks::Editor(
+            "editor://open?file={path}&line={line}&col={col}".into(),
+        ))
+        .remap_path_prefix(env!("CARGO_MANIFEST_DIR"), "/my project#1")
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("error occured"));
+    // The path is percent-encoded
+    let location =
+        "\u{1b}]8;;editor://open?file=/my%20project%231/tests/hyperlinks.rs&line=17&col=34\u{1b}\\";
+    assert!(report.contains(location), "{}", report);
+    assert!(report.contains("\u{1b}]8;;\u{1b}\\"));
+}
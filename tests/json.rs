@@ -0,0 +1,32 @@
+use color_eyre::{config::OutputFormat, eyre::eyre, Section};
+
+#[test]
+fn json_output() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    color_eyre::config::HookBuilder::default()
+        .output_format(OutputFormat::Json)
+        .remap_path_prefix("tests", "/remapped")
+        .install()
+        .unwrap();
+
+    let report = eyre!("error occured").note("a \"quoted\" note");
+
+    let report = format!("{:?}", report);
+    assert!(report.starts_with(r#"{"kind":"error","errors":["error occured"]"#));
+    assert!(report.contains(r#"{"kind":"note","message":"a \"quoted\" note"}"#));
+    assert!(report.contains(r#""backtrace":[{"n":"#));
+    assert!(!report.contains('\x1b'));
+
+    #[cfg(feature = "capture-spantrace")]
+    {
+        use tracing_subscriber::prelude::*;
+        tracing_subscriber::registry()
+            .with(tracing_error::ErrorLayer::default())
+            .init();
+        let _span = tracing::info_span!("json_output").entered();
+
+        let report = format!("{:?}", eyre!("error occured"));
+        assert!(report.contains(r#""name":"json_output","fields":"","file":"/remapped/json.rs""#));
+    }
+}
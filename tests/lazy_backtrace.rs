@@ -0,0 +1,18 @@
+use color_eyre::{eyre::eyre, Handler};
+
+#[test]
+fn backtrace_is_resolved_on_access() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    color_eyre::install().unwrap();
+
+    let report = eyre!("error occured");
+    let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    let backtrace = handler.backtrace().unwrap();
+
+    assert!(backtrace
+        .frames()
+        .iter()
+        .flat_map(|frame| frame.symbols())
+        .any(|symbol| symbol.name().is_some()));
+    assert!(std::ptr::eq(backtrace, handler.backtrace().unwrap()));
+}
@@ -0,0 +1,27 @@
+use color_eyre::{config::HookBuilder, eyre::eyre};
+
+#[test]
+fn own_code() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    // Pretend this test is the module `vendor::registry::src` of an own crate,
+    // which looks like a vendored crate
+    HookBuilder::default()
+        .add_own_crate("eyre")
+        .remap_path_prefix(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests"),
+            "/workspace/src/vendor/registry/src",
+        )
+        .add_own_path("/workspace")
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("error occured"));
+
+    // Frames of own crates are no longer hidden by the default filters
+    assert!(!report.contains("hidden (eyre"));
+    // and styled as crate code
+    assert!(report.contains("\u{1b}[91meyre::"));
+    // Module names of own paths don't make them dependency code
+    assert!(report.contains("\u{1b}[91mown_code::own_code"));
+}
@@ -0,0 +1,103 @@
+use color_eyre::config::{HookBuilder, PanicWriter};
+use std::sync::{Arc, Mutex};
+
+#[allow(deprecated)]
+fn payload(panic_info: &std::panic::PanicInfo<'_>) -> &'static str {
+    panic_info.payload().downcast_ref::<&str>().unwrap()
+}
+
+/// Run the test `name` in a child process and return its output, or `None`
+/// if this is the child process. The hooks can only be installed once per
+/// process, and a panicking callback aborts it.
+fn child_process(name: &str) -> Option<std::process::Output> {
+    if std::env::var_os("COLOR_EYRE_TEST_CHILD").is_some() {
+        return None;
+    }
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([name, "--exact", "--nocapture"])
+        .env("COLOR_EYRE_TEST_CHILD", name)
+        .output()
+        .unwrap();
+    Some(output)
+}
+
+#[test]
+fn panic_callbacks() {
+    if let Some(output) = child_process("panic_callbacks") {
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return;
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let (before, report, after) = (events.clone(), events.clone(), events.clone());
+    HookBuilder::default()
+        .on_panic_before(move |panic_info| {
+            before
+                .lock()
+                .unwrap()
+                .push(format!("before {}", payload(panic_info)));
+
+            // Panics in other threads run the callbacks as well
+            if payload(panic_info) == "outer" {
+                std::thread::spawn(|| panic!("inner")).join().unwrap_err();
+            }
+        })
+        .on_panic_after(move |panic_info| {
+            after
+                .lock()
+                .unwrap()
+                .push(format!("after {}", payload(panic_info)))
+        })
+        .panic_writer(PanicWriter::callback(move |text| {
+            let panic = if text.contains("inner") {
+                "inner"
+            } else {
+                "outer"
+            };
+            report.lock().unwrap().push(format!("report {}", panic))
+        }))
+        .install()
+        .unwrap();
+
+    std::panic::catch_unwind(|| panic!("outer")).unwrap_err();
+
+    let events = events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        [
+            "before outer",
+            "before inner",
+            "report inner",
+            "after inner",
+            "report outer",
+            "after outer"
+        ]
+    );
+}
+
+#[test]
+fn panicking_callback() {
+    if let Some(output) = child_process("panicking_callback") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "{}", stderr);
+        // The panic inside the callback isn't reported
+        assert_eq!(stderr.matches("The application panicked").count(), 1);
+        assert!(stderr.contains("expected panic"));
+        assert!(!stderr.contains("callback panicked"));
+        return;
+    }
+
+    HookBuilder::default()
+        .on_panic_after(|_| panic!("callback panicked"))
+        .install()
+        .unwrap();
+
+    std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+    unreachable!("the panic inside the callback aborts the process");
+}
@@ -0,0 +1,45 @@
+use color_eyre::config::{HookBuilder, PanicWriter};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[test]
+fn panic_thread() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+
+    let writer_reports = reports.clone();
+    HookBuilder::default()
+        .count_panics(true)
+        .panic_rate_limit(2, Duration::from_secs(60))
+        .remap_path_prefix("tests", "remapped")
+        .panic_writer(
+            PanicWriter::callback(move |report| {
+                writer_reports.lock().unwrap().push(report.to_owned())
+            })
+            .colored(false),
+        )
+        .install()
+        .unwrap();
+
+    for worker in 0..4 {
+        std::thread::Builder::new()
+            .name(format!("worker-{}", worker))
+            .spawn(|| panic!("expected panic"))
+            .unwrap()
+            .join()
+            .unwrap_err();
+    }
+
+    let reports = reports.lock().unwrap().clone();
+    assert_eq!(reports.len(), 4);
+    assert!(reports[0].contains("\nThread:   'worker-0', panic 1 of this process\n"));
+    assert!(reports[1].contains("\nThread:   'worker-1', panic 2 of this process\n"));
+    for (report, count) in reports[2..].iter().zip(3..) {
+        assert!(report.starts_with(&format!(
+            "Suppressed the report of a repeated panic ({} times in 60s): expected panic at remapped/panic_thread.rs:",
+            count
+        )));
+        assert_eq!(report.lines().count(), 1);
+    }
+}
@@ -0,0 +1,38 @@
+use color_eyre::config::{HookBuilder, PanicWriter};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn panic_writer() {
+    let colored = Arc::new(Mutex::new(String::new()));
+    let plain = Arc::new(Mutex::new(Vec::new()));
+
+    let colored_report = colored.clone();
+    let plain_report = plain.clone();
+    HookBuilder::default()
+        .panic_writer(PanicWriter::callback(move |report| {
+            colored_report.lock().unwrap().push_str(report)
+        }))
+        .panic_writer(PanicWriter::new(move || SharedBuf(plain_report.clone())).colored(false))
+        .install()
+        .unwrap();
+
+    std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+
+    let colored = colored.lock().unwrap();
+    let plain = String::from_utf8(plain.lock().unwrap().clone()).unwrap();
+    assert!(colored.contains("\u{1b}[36mexpected panic\u{1b}[0m"));
+    assert!(plain.contains("Message:  expected panic\n"));
+    assert!(!plain.contains('\u{1b}'));
+}
+
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
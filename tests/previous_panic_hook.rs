@@ -0,0 +1,94 @@
+use color_eyre::config::{HookBuilder, PanicWriter, PreviousPanicHook};
+use std::sync::{Arc, Mutex};
+
+/// Whether this process runs only the test `name`. Otherwise `name` is run in
+/// a child process and checked for success, as the hooks can only be created
+/// once per process.
+fn in_child_process(name: &str) -> bool {
+    if std::env::var_os("COLOR_EYRE_TEST_CHILD").is_some() {
+        return true;
+    }
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([name, "--exact"])
+        .env("COLOR_EYRE_TEST_CHILD", name)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    false
+}
+
+#[test]
+fn previous_panic_hook() {
+    if !in_child_process("previous_panic_hook") {
+        return;
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let previous = events.clone();
+    std::panic::set_hook(Box::new(move |_| previous.lock().unwrap().push("previous")));
+
+    let report = events.clone();
+    HookBuilder::default()
+        .previous_panic_hook(PreviousPanicHook::CallAfter)
+        .panic_writer(PanicWriter::callback(move |_| {
+            report.lock().unwrap().push("report")
+        }))
+        .install()
+        .unwrap();
+
+    std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+
+    let events = events.lock().unwrap().clone();
+    assert_eq!(events, ["report", "previous"]);
+}
+
+#[test]
+fn previous_panic_hook_instead() {
+    if !in_child_process("previous_panic_hook_instead") {
+        return;
+    }
+
+    std::env::remove_var("RUST_BACKTRACE");
+
+    let dir = std::env::temp_dir().join(format!("color-eyre-instead-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let previous = events.clone();
+    std::panic::set_hook(Box::new(move |_| previous.lock().unwrap().push("previous")));
+
+    let after = events.clone();
+    HookBuilder::default()
+        .previous_panic_hook(PreviousPanicHook::CallInstead)
+        .crash_report_dir(&dir)
+        .count_panics(true)
+        .on_panic_after(move |_| after.lock().unwrap().push("after"))
+        .install()
+        .unwrap();
+
+    for _ in 0..2 {
+        std::panic::catch_unwind(|| panic!("expected panic")).unwrap_err();
+    }
+
+    let events = events.lock().unwrap().clone();
+    assert_eq!(events, ["previous", "after", "previous", "after"]);
+
+    // The crash reports are still saved, numbered like the panics
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    files.sort_by_key(|text| text.contains("panic 2 of this process"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert!(files[0].contains("panic 1 of this process"));
+    assert!(files[1].contains("panic 2 of this process"));
+}
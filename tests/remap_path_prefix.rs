@@ -0,0 +1,25 @@
+use color_eyre::eyre::eyre;
+
+#[test]
+fn remapped() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    std::env::remove_var("HOME");
+    std::env::remove_var("USERPROFILE");
+
+    color_eyre::config::HookBuilder::default()
+        .remap_path_prefix(
+            env!("CARGO_MANIFEST_DIR"),
+            "/opt/.cargo/registry/src/index.crates.io-0/app-1.0.0",
+        )
+        .shorten_paths(true)
+        .install()
+        .unwrap();
+
+    let report = eyre!("error occured");
+
+    let report = format!("{:?}", report);
+    assert!(report.contains("app-1.0.0/tests/remap_path_prefix.rs"));
+    assert!(!report.contains(env!("CARGO_MANIFEST_DIR")));
+    // Registry paths are shortened even if the home directory is unknown
+    assert!(!report.contains("/opt/.cargo/registry"));
+}
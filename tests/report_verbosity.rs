@@ -0,0 +1,42 @@
+use color_eyre::{config::Verbosity, eyre::eyre, Section};
+
+#[test]
+fn report_verbosity() {
+    std::env::remove_var("RUST_BACKTRACE");
+    std::env::remove_var("RUST_LIB_BACKTRACE");
+    color_eyre::install().unwrap();
+
+    // The backtrace is captured when the verbosity is raised
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(!report.contains(" BACKTRACE "));
+    let report = format!("{:?}", eyre!("error occured").verbosity(Verbosity::Full));
+    assert!(report.contains(" BACKTRACE "));
+    assert!(report.contains("at address"));
+    assert!(!report.contains("to include source snippets"));
+
+    let report = format!("{:?}", eyre!("error occured").verbosity(Verbosity::Minimal));
+    assert!(!report.contains(" BACKTRACE "));
+    assert!(!report.contains("COLORBT_SHOW_HIDDEN"));
+    assert!(!report.contains("RUST_BACKTRACE"));
+
+    let result: Result<(), _> = Err(eyre!("error occured"));
+    let report = format!("{:?}", result.suppress_env_section(true).unwrap_err());
+    assert!(!report.contains("Backtrace omitted"));
+    assert!(!report.contains("RUST_BACKTRACE"));
+
+    #[cfg(feature = "capture-spantrace")]
+    {
+        use tracing_subscriber::prelude::*;
+        tracing_subscriber::registry()
+            .with(tracing_error::ErrorLayer::default())
+            .init();
+        let _span = tracing::info_span!("report_verbosity").entered();
+
+        let report = format!("{:?}", eyre!("error occured"));
+        assert!(report.contains(" SPANTRACE "));
+
+        let result: Result<(), _> = Err(eyre!("error occured"));
+        let report = format!("{:?}", result.suppress_span_trace(true).unwrap_err());
+        assert!(!report.contains(" SPANTRACE "));
+    }
+}
@@ -0,0 +1,21 @@
+use color_eyre::{config::HookBuilder, eyre::eyre};
+
+#[test]
+fn simplify_symbols() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    HookBuilder::default()
+        .simplify_symbol_names(true)
+        .display_symbol_hashes(false)
+        .install()
+        .unwrap();
+
+    let outer = || {
+        let inner = || eyre!("error occured");
+        std::hint::black_box(inner())
+    };
+    let report = format!("{:?}", outer());
+    assert!(report.contains("simplify_symbols::simplify_symbols::{{closure}}\u{1b}[0m\n"));
+    assert!(!report.contains("{{closure}}::{{closure}}"));
+    assert!(!report.contains("core::ops::function::FnOnce"));
+    assert!(!report.contains("::h"));
+}
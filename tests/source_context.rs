@@ -0,0 +1,28 @@
+use color_eyre::eyre::eyre;
+
+#[test]
+fn rest_of_file() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "full");
+
+    color_eyre::config::HookBuilder::default()
+        .source_context(0, u32::MAX)
+        .dependency_source_snippets(false)
+        .install()
+        .unwrap();
+
+    // Not part of the snippet
+    let report = eyre!("error occured");
+
+    let report = format!("{:?}", report);
+    // The snippet of the frame creating the report
+    let snippet = report
+        .split("source_context::rest_of_file\u{1b}")
+        .nth(1)
+        .and_then(|frame| frame.split("::{{closure}}\u{1b}").next())
+        .unwrap();
+    assert!(snippet.contains("let report = eyre!(\"error occured\");"));
+    assert!(!snippet.contains(concat!("// Not part", " of the snippet")));
+    // The snippet runs until the end of the file
+    assert!(snippet.contains(concat!("// Last line", " of the file")));
+}
+// Last line of the file
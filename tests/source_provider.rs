@@ -0,0 +1,35 @@
+use color_eyre::{config::SourceProvider, eyre::eyre};
+use std::{io, ops::RangeInclusive, path::Path};
+
+struct TestSource;
+
+impl SourceProvider for TestSource {
+    fn source_lines(
+        &self,
+        path: &Path,
+        lines: RangeInclusive<u32>,
+    ) -> io::Result<Option<Vec<String>>> {
+        if path.ends_with("tests/source_provider.rs") {
+            Ok(Some(
+                lines.map(|n| format!("provided line {}", n)).collect(),
+            ))
+        } else {
+            Err(io::Error::other("unavailable"))
+        }
+    }
+}
+
+#[test]
+fn custom_source_provider() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "full");
+
+    color_eyre::config::HookBuilder::default()
+        .source_provider(TestSource)
+        .install()
+        .unwrap();
+
+    let report = eyre!("error occured");
+
+    let report = format!("{:?}", report);
+    assert!(report.contains("provided line"));
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "syntax-highlighting")]
+use color_eyre::{config::Theme, eyre::eyre};
+use owo_colors::style;
+
+#[test]
+fn highlighted_snippet() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "full");
+
+    color_eyre::config::HookBuilder::default()
+        .theme(
+            Theme::new()
+                .syntax_keyword(style().red())
+                .syntax_string(style().green()),
+        )
+        .install()
+        .unwrap();
+
+    let report = eyre!("error occured");
+
+    let report = format!("{:?}", report);
+    assert!(report.contains("\u{1b}[31mlet\u{1b}[0m report"));
+}
@@ -147,7 +147,7 @@ fn test_backwards_compatibility(target: String, file_name: &str) {
     // `unwrap` should never fail with files generated by this function
     let control = String::from_utf8(fs::read(file_path).unwrap()).unwrap();
 
-    fn f(s: &str) -> (Vec<Output>, Vec<AnsiSequence>) {
+    fn f(s: &str) -> (Vec<Output<'_>>, Vec<AnsiSequence>) {
         let all: Vec<_> = s.ansi_parse().collect();
         let ansi: Vec<_> = s
             .ansi_parse()
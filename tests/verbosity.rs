@@ -0,0 +1,44 @@
+use color_eyre::{
+    config::{set_verbosity, HookBuilder, Verbosity},
+    eyre::eyre,
+};
+
+#[test]
+fn verbosity() {
+    std::env::remove_var("RUST_BACKTRACE");
+    std::env::remove_var("RUST_LIB_BACKTRACE");
+    HookBuilder::default()
+        .verbosity(Verbosity::Full)
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(report.contains(" BACKTRACE "));
+    assert!(report.contains("at address"));
+
+    // The environment variables have no effect, so they aren't suggested
+    set_verbosity(Some(Verbosity::Medium));
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(report.contains(" BACKTRACE "));
+    assert!(!report.contains("at address"));
+    assert!(!report.contains("RUST_BACKTRACE"));
+
+    set_verbosity(Some(Verbosity::Minimal));
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(!report.contains(" BACKTRACE "));
+    assert!(!report.contains("RUST_BACKTRACE"));
+
+    // Fall back to the unset environment variables
+    set_verbosity(None);
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(!report.contains(" BACKTRACE "));
+    assert!(report.contains("Run with RUST_BACKTRACE=1"));
+
+    // Hooks that fail to install don't change the verbosity
+    assert!(HookBuilder::default()
+        .verbosity(Verbosity::Full)
+        .install()
+        .is_err());
+    let report = format!("{:?}", eyre!("error occured"));
+    assert!(!report.contains(" BACKTRACE "));
+}
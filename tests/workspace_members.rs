@@ -0,0 +1,33 @@
+use color_eyre::{config::HookBuilder, eyre::eyre};
+use std::path::PathBuf;
+
+#[test]
+fn workspace_members() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    // As set by `color_eyre::workspace::detect_workspace` in a build script
+    let root = PathBuf::from("/build/project");
+    let member = root.join("vendor").join("app");
+    let members = std::env::join_paths([&member, &root]).unwrap();
+
+    // Pretend this test is part of a member that looks like a vendored crate,
+    // and that the registry sources of eyre live in a `CARGO_HOME` inside the
+    // workspace
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(std::env::var_os("HOME").unwrap()).join(".cargo"));
+    HookBuilder::default()
+        .remap_path_prefix(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests"),
+            member.join("src"),
+        )
+        .remap_path_prefix(cargo_home, root.join(".cargo"))
+        .workspace_members(members.to_str().unwrap())
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("error occured"));
+
+    assert!(report.contains("\u{1b}[91mworkspace_members::workspace_members"));
+    assert!(report.contains("hidden (eyre"));
+}